@@ -1,7 +1,11 @@
+use crate::{
+    constants::{FIELD_LENGTH, FIELD_MARGIN_Y, FIELD_WIDTH},
+    hardware::MOTOR_COUNT,
+};
 use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex};
 
 macro_rules! init_config {
-    ($($name:ident: $type:ty = $value:literal),*,) => {
+    ($($name:ident: $type:ty = $value:expr),*,) => {
         pub static CONFIG: Mutex<CriticalSectionRawMutex, Config> = Mutex::new(Config {
             $(
                 $name: $value,
@@ -21,10 +25,22 @@ init_config! {
     print_imu: bool = false,
     angle: f32 = 999.,
     goalie: bool = false,
+    mirrored: bool = false,
+    kickoff_striker: (f32, f32) = (FIELD_WIDTH / 2., FIELD_LENGTH / 2. + 20.),
+    kickoff_goalie: (f32, f32) = (FIELD_WIDTH / 2., FIELD_LENGTH - FIELD_MARGIN_Y - 5.),
+    planning: bool = true,
+    stuck_duration: u64 = 1500,
     pid_p: f32 = 0.04,
+    pid_i: f32 = 0.,
+    pid_i_max: f32 = 0.2,
     pid_d: f32 = 0.13,
     pid2_p: f32 = 0.02,
     pid2_d: f32 = 0.,
+    rotation_priority: f32 = 1.,
+    braking_distance: f32 = 25.,
+    motor_min: [f32; MOTOR_COUNT] = [26.; MOTOR_COUNT],
+    motor_gain: [f32; MOTOR_COUNT] = [1.; MOTOR_COUNT],
+    motor_direction: [f32; MOTOR_COUNT] = [1.; MOTOR_COUNT],
 }
 
 macro_rules! get_config {
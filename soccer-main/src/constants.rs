@@ -11,3 +11,11 @@ pub const CLEARANCE_X: f32 = 20.;
 
 pub const BALLCAP_DISTANCE: f32 = 7.;
 pub const BALLCAP_WIDTH: f32 = 4.;
+
+pub const ROBOT_RADIUS: f32 = 9.;
+
+pub const GOAL_WIDTH: f32 = 60.;
+pub const GOAL_DEPTH: f32 = 8.;
+
+pub const PENALTY_WIDTH: f32 = 80.;
+pub const PENALTY_DEPTH: f32 = 25.;
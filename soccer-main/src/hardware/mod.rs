@@ -8,6 +8,9 @@ pub mod motor;
 pub mod temts;
 pub mod uart;
 
+// also the number of wheels in the kinematics and of entries in the motor calibration config
+pub const MOTOR_COUNT: usize = 4;
+
 pub static UART_CHANNEL: Channel<CriticalSectionRawMutex, Command, 4> = Channel::new();
 
 pub static CAMERA_SIGNAL: Signal<CriticalSectionRawMutex, CameraData> = Signal::new();
@@ -41,6 +44,7 @@ pub struct LidarData {
 
 pub struct ImuData {
     pub angle: f32,
+    pub rate: f32,
 }
 
 pub struct CameraData {
@@ -50,9 +54,15 @@ pub struct CameraData {
     pub goal_dist: f32,
 }
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum MotorMode {
+    Drive,
+    Coast,
+    Brake,
+}
+
+// in the order of fl, fr, bl, br
 pub struct MotorData {
-    pub fl: i16,
-    pub fr: i16,
-    pub bl: i16,
-    pub br: i16,
+    pub speeds: [i16; MOTOR_COUNT],
+    pub modes: [MotorMode; MOTOR_COUNT],
 }
@@ -1,4 +1,7 @@
-use crate::{hardware::MOTOR_SIGNAL, peripherals::PeripheralsMotor};
+use crate::{
+    hardware::{MotorMode, MOTOR_SIGNAL},
+    peripherals::PeripheralsMotor,
+};
 use defmt::info;
 use embassy_executor::Spawner;
 use embassy_rp::{
@@ -23,16 +26,21 @@ impl<T: Channel> Motor<T> {
         Self(Pwm::new_output_ab(inner, a, b, config))
     }
 
-    fn set_speed(&mut self, speed: i16) {
+    fn set_speed(&mut self, speed: i16, mode: MotorMode) {
         let mut config = Config::default();
 
         config.top = 254;
         config.divider = 255.into();
 
-        if speed >= 0 {
-            config.compare_a = speed as u16;
-        } else {
-            config.compare_b = (-speed) as u16;
+        match mode {
+            MotorMode::Drive if speed >= 0 => config.compare_a = speed as u16,
+            MotorMode::Drive => config.compare_b = (-speed) as u16,
+            MotorMode::Coast => {}
+            MotorMode::Brake => {
+                // both sides held high shorts the motor
+                config.compare_a = config.top + 1;
+                config.compare_b = config.top + 1;
+            }
         }
 
         self.0.set_config(&config);
@@ -49,10 +57,10 @@ async fn motor_task(
     loop {
         let data = MOTOR_SIGNAL.wait().await;
 
-        motor_fl.set_speed(data.fl);
-        motor_fr.set_speed(data.fr);
-        motor_bl.set_speed(data.bl);
-        motor_br.set_speed(data.br);
+        motor_fl.set_speed(data.speeds[0], data.modes[0]);
+        motor_fr.set_speed(data.speeds[1], data.modes[1]);
+        motor_bl.set_speed(data.speeds[2], data.modes[2]);
+        motor_br.set_speed(data.speeds[3], data.modes[3]);
     }
 }
 
@@ -92,7 +92,8 @@ async fn uart_rx_task(mut rx: UartRx<'static, UART1, Async>) {
                     });
                 }
                 2 => {
-                    if len != 3 {
+                    // vision boards on older firmware send the angle without the rate
+                    if len != 3 && len != 5 {
                         continue;
                     }
 
@@ -103,9 +104,15 @@ async fn uart_rx_task(mut rx: UartRx<'static, UART1, Async>) {
 
                     let angle = (i16::from_le_bytes([buf[1], buf[2]]) as f32) / 128.;
                     let angle = clamp_angle(angle - zero_angle);
+                    let rate = if len == 5 {
+                        (i16::from_le_bytes([buf[3], buf[4]]) as f32) / 16.
+                    } else {
+                        0.
+                    };
 
                     IMU_SIGNAL.signal(ImuData {
                         angle: clamp_angle(angle),
+                        rate,
                     });
                 }
                 _ => {
@@ -12,7 +12,8 @@ use embassy_rp::{
     config::Config,
     multicore::{spawn_core1, Stack},
 };
-use embassy_time::Timer;
+use embassy_time::{Instant, Timer};
+use modules::game::Event;
 use static_cell::StaticCell;
 
 #[cfg(feature = "network")]
@@ -23,6 +24,7 @@ mod hardware;
 mod modules;
 #[cfg(feature = "network")]
 mod network;
+mod params;
 mod peripherals;
 mod strategy;
 mod utils;
@@ -31,6 +33,10 @@ mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
 
+const BUTTON_INTERVAL: u64 = 10;
+const BUTTON_HOLD: u64 = 1000;
+const BUTTON_DOUBLE: u64 = 300;
+
 static mut STACK1: Stack<65536> = Stack::new();
 static EXECUTOR0: StaticCell<Executor> = StaticCell::new();
 static EXECUTOR1: StaticCell<Executor> = StaticCell::new();
@@ -53,11 +59,35 @@ async fn core0_task(spawner: Spawner, p: Peripherals0) {
     hardware::temts::init(&spawner, p.temts).await;
 
     let mut button = p.button.BOOTSEL;
-    while !config::get_config!(started) {
+    loop {
         if button.is_pressed() {
-            utils::start().await;
+            let pressed = Instant::now();
+            while button.is_pressed() {
+                Timer::after_millis(BUTTON_INTERVAL).await;
+            }
+
+            if pressed.elapsed().as_millis() > BUTTON_HOLD {
+                modules::game::send(Event::Hold).await;
+            } else {
+                // a second press straight after the first switches sides instead
+                let released = Instant::now();
+                let mut double = false;
+                while !double && released.elapsed().as_millis() < BUTTON_DOUBLE {
+                    double = button.is_pressed();
+                    Timer::after_millis(BUTTON_INTERVAL).await;
+                }
+                while button.is_pressed() {
+                    Timer::after_millis(BUTTON_INTERVAL).await;
+                }
+
+                if double {
+                    modules::side::switch().await;
+                } else {
+                    modules::game::send(Event::Button).await;
+                }
+            }
         }
-        Timer::after_millis(10).await;
+        Timer::after_millis(BUTTON_INTERVAL).await;
     }
 }
 
@@ -67,8 +97,13 @@ async fn core1_task(spawner: Spawner, _p: Peripherals1) {
 
     modules::ball::init(&spawner).await;
     modules::coordinate::init(&spawner).await;
+    modules::field::init(&spawner).await;
+    modules::game::init(&spawner).await;
     modules::heading::init(&spawner).await;
     modules::movement::init(&spawner).await;
+    modules::role::init(&spawner).await;
+    modules::stuck::init(&spawner).await;
+    modules::trajectory::init(&spawner).await;
 
     strategy::init(&spawner).await;
 }
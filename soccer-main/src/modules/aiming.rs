@@ -0,0 +1,81 @@
+use crate::{
+    constants::{FIELD_MARGIN, FIELD_WIDTH, GOAL_WIDTH, ROBOT_RADIUS},
+    utils::construct_vector,
+};
+use heapless::Vec;
+use num_traits::Float;
+
+const BALL_RADIUS: f32 = 2.1;
+const POST_MARGIN: f32 = 4.;
+const GAP_MIN: f32 = 2.;
+
+// angle from the ball to a point, clockwise from the direction of attack
+fn bearing(ball: (f32, f32), point: (f32, f32)) -> f32 {
+    let (_, angle) = construct_vector(point.0 - ball.0, ball.1 - point.1);
+    angle.to_degrees()
+}
+
+// picks the middle of the widest part of the goal mouth that no obstacle covers as seen from the
+// ball, falling back to the centre of the goal when it is fully blocked
+pub fn aim(ball: (f32, f32), obstacles: &[Option<(f32, f32)>]) -> (f32, f32) {
+    let centre = (FIELD_WIDTH / 2., FIELD_MARGIN);
+
+    if ball.1 <= FIELD_MARGIN {
+        return centre;
+    }
+
+    let left = bearing(
+        ball,
+        ((FIELD_WIDTH - GOAL_WIDTH) / 2. + POST_MARGIN, FIELD_MARGIN),
+    );
+    let right = bearing(
+        ball,
+        ((FIELD_WIDTH + GOAL_WIDTH) / 2. - POST_MARGIN, FIELD_MARGIN),
+    );
+
+    let mut blocked: Vec<(f32, f32), 4> = Vec::new();
+    for &(ox, oy) in obstacles.iter().flatten() {
+        if oy >= ball.1 || oy < FIELD_MARGIN - ROBOT_RADIUS {
+            continue;
+        }
+
+        let (distance, _) = construct_vector(ox - ball.0, ball.1 - oy);
+        let width = ((ROBOT_RADIUS + BALL_RADIUS) / distance.max(ROBOT_RADIUS + BALL_RADIUS))
+            .asin()
+            .to_degrees();
+        let angle = bearing(ball, (ox, oy));
+
+        let _ = blocked.push((angle - width, angle + width));
+    }
+
+    blocked.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut best = (0., centre.0);
+    let mut start = left;
+
+    let mut consider = |from: f32, to: f32| {
+        if to - from > best.0 {
+            let middle = ((from + to) / 2.).to_radians();
+            best = (to - from, ball.0 + (ball.1 - FIELD_MARGIN) * middle.tan());
+        }
+    };
+
+    for &(from, to) in &blocked {
+        if from > start {
+            consider(start, from.min(right));
+        }
+        start = start.max(to);
+        if start >= right {
+            break;
+        }
+    }
+    if start < right {
+        consider(start, right);
+    }
+
+    if best.0 < GAP_MIN {
+        return centre;
+    }
+
+    (best.1, FIELD_MARGIN)
+}
@@ -1,15 +1,21 @@
 use crate::{
+    constants::FIELD_LENGTH,
     hardware::CAMERA_SIGNAL,
     modules::{
-        BALL_CHANGED, BALL_MUTEX, COORDINATE_CHANGED, COORDINATE_MUTEX, GOAL_MUTEX, HEADING_MUTEX,
+        side, BALL_CHANGED, BALL_MUTEX, BALL_SEEN_MUTEX, BALL_VELOCITY_MUTEX, COORDINATE_CHANGED,
+        COORDINATE_MUTEX, GOAL_MUTEX, HEADING_MUTEX,
     },
     utils::{clamp_angle, debug::debug_variable, read_mutex, write_mutex},
 };
 use defmt::info;
 use embassy_executor::Spawner;
 use embassy_futures::select::{select, Either};
+use embassy_time::Instant;
 use nalgebra::{Rotation2, Vector2};
 
+const VELOCITY_FILTER: f32 = 0.3;
+const VELOCITY_TIMEOUT: f32 = 0.5;
+
 #[embassy_executor::task]
 async fn ball_task() {
     let publisher = BALL_CHANGED.immediate_publisher();
@@ -19,6 +25,9 @@ async fn ball_task() {
     #[allow(unused_assignments)]
     let (mut x, mut y, mut ok) = read_mutex!(COORDINATE_MUTEX);
 
+    let mut last_ball: Option<(f32, f32, Instant)> = None;
+    let mut velocity = (0., 0.);
+
     loop {
         let is_camera = match select(CAMERA_SIGNAL.wait(), subscriber.next_message()).await {
             Either::First(data) => {
@@ -45,6 +54,10 @@ async fn ball_task() {
         if camera.angle == 0. && camera.dist == 0. {
             let (bx, by, _) = read_mutex!(BALL_MUTEX);
             write_mutex!(BALL_MUTEX, (bx, by, false));
+
+            last_ball = None;
+            velocity = (0., 0.);
+            write_mutex!(BALL_VELOCITY_MUTEX, velocity);
         } else {
             let angle = clamp_angle(heading + camera.angle);
             let vector = Vector2::new(x, y);
@@ -53,6 +66,25 @@ async fn ball_task() {
             let vector = vector - rotation * translation;
 
             write_mutex!(BALL_MUTEX, (vector.x, vector.y, true));
+            write_mutex!(BALL_SEEN_MUTEX, Instant::now());
+
+            // only fresh camera frames are new measurements of the ball
+            if is_camera && ok {
+                if let Some((last_x, last_y, last_time)) = last_ball {
+                    let dt = last_time.elapsed().as_micros() as f32 / 1000000.;
+                    if dt > VELOCITY_TIMEOUT {
+                        velocity = (0., 0.);
+                    } else if dt > f32::EPSILON {
+                        velocity.0 += ((vector.x - last_x) / dt - velocity.0) * VELOCITY_FILTER;
+                        velocity.1 += ((vector.y - last_y) / dt - velocity.1) * VELOCITY_FILTER;
+                    }
+                }
+                last_ball = Some((vector.x, vector.y, Instant::now()));
+
+                write_mutex!(BALL_VELOCITY_MUTEX, velocity);
+                debug_variable!("ball vx", velocity.0);
+                debug_variable!("ball vy", velocity.1);
+            }
         }
 
         if camera.goal_angle == 0. && camera.goal_dist == 0. {
@@ -65,7 +97,10 @@ async fn ball_task() {
             let translation = Vector2::y() * camera.goal_dist;
             let vector = vector - rotation * translation;
 
-            write_mutex!(GOAL_MUTEX, (vector.x, vector.y, true));
+            // the camera looks for one colour, which after switching sides is our own goal, so a
+            // sighting then only counts when a good position puts it in the half we attack
+            let valid = !side::mirrored().await || (ok && vector.y < FIELD_LENGTH / 2.);
+            write_mutex!(GOAL_MUTEX, (vector.x, vector.y, valid));
         }
 
         publisher.publish_immediate(is_camera);
@@ -0,0 +1,134 @@
+use crate::{
+    config::{get_config, set_config},
+    hardware::{MotorData, MotorMode, MOTOR_COUNT, MOTOR_SIGNAL},
+    modules::{kinematics::WHEELS, COORDINATE_MUTEX, HEADING_MUTEX},
+    utils::{clamp_angle, construct_vector, debug::debug_variable, read_mutex, stop},
+};
+use defmt::{info, warn};
+use embassy_time::{Instant, Timer};
+use num_traits::Float;
+
+const RAMP_STEP: f32 = 2.;
+const RAMP_DURATION: u64 = 100;
+const MOVED_ANGLE: f32 = 2.;
+const MOVED_DISTANCE: f32 = 3.;
+const SPIN_SPEED: f32 = 0.5;
+const SPIN_DURATION: u64 = 1000;
+const REST_DURATION: u64 = 500;
+const SAMPLE_DURATION: u64 = 10;
+
+fn spin(motor: usize, speed: f32) {
+    let mut speeds = [0; MOTOR_COUNT];
+    speeds[motor] = speed.round() as i16;
+    MOTOR_SIGNAL.signal(MotorData {
+        speeds,
+        modes: [MotorMode::Drive; MOTOR_COUNT],
+    });
+}
+
+async fn measure_rotation(duration: u64) -> f32 {
+    let start = Instant::now();
+    let mut last = read_mutex!(HEADING_MUTEX);
+    let mut total = 0.;
+
+    while start.elapsed().as_millis() < duration {
+        Timer::after_millis(SAMPLE_DURATION).await;
+        let heading = read_mutex!(HEADING_MUTEX);
+        total += clamp_angle(heading - last);
+        last = heading;
+    }
+
+    total
+}
+
+// compares against where the ramp started rather than adding up every step, so that gyro and lidar
+// noise cannot build up into a false start
+async fn moved(heading: f32, (x, y, ok): (f32, f32, bool)) -> bool {
+    let turned = clamp_angle(read_mutex!(HEADING_MUTEX) - heading).abs() > MOVED_ANGLE;
+
+    let (new_x, new_y, new_ok) = read_mutex!(COORDINATE_MUTEX);
+    let shifted = ok && new_ok && construct_vector(new_x - x, new_y - y).0 > MOVED_DISTANCE;
+
+    turned || shifted
+}
+
+pub async fn calibrate_motors(apply: bool) {
+    stop().await;
+
+    let mut motor_min = get_config!(motor_min);
+    let mut motor_gain = get_config!(motor_gain);
+    let mut motor_direction = get_config!(motor_direction);
+
+    let mut changes = [0.; MOTOR_COUNT];
+    let mut failed = [false; MOTOR_COUNT];
+
+    for wheel in WHEELS.iter() {
+        let motor = wheel.motor;
+        debug_variable!("calibration motor", motor);
+
+        let heading = read_mutex!(HEADING_MUTEX);
+        let coordinates = read_mutex!(COORDINATE_MUTEX);
+        let mut speed = 0.;
+        let mut started = false;
+
+        while speed < 255. - RAMP_STEP && !started {
+            speed += RAMP_STEP;
+            spin(motor, speed);
+            Timer::after_millis(RAMP_DURATION).await;
+            started = moved(heading, coordinates).await;
+        }
+
+        spin(motor, 0.);
+        Timer::after_millis(REST_DURATION).await;
+
+        // a disconnected wheel, a lifted robot or no heading and position all look like this, and
+        // none of them say anything about the motor
+        if !started {
+            warn!("Motor {} never moved the robot", motor);
+            failed[motor] = true;
+            continue;
+        }
+
+        motor_min[motor] = (speed - RAMP_STEP).max(0.);
+
+        spin(
+            motor,
+            motor_min[motor] + SPIN_SPEED * (255. - motor_min[motor]),
+        );
+        let change = measure_rotation(SPIN_DURATION).await;
+
+        spin(motor, 0.);
+        Timer::after_millis(REST_DURATION).await;
+
+        // forward spins should turn the robot clockwise, so the motor is wired backwards otherwise
+        motor_direction[motor] = if change < 0. { -1. } else { 1. };
+        changes[motor] = change.abs();
+
+        info!(
+            "Calibrated motor {}: min {}, change {}",
+            motor, motor_min[motor], change
+        );
+    }
+
+    let working = failed.iter().filter(|failed| !**failed).count();
+    let mean = changes.iter().sum::<f32>() / working.max(1) as f32;
+
+    for wheel in WHEELS.iter() {
+        let motor = wheel.motor;
+        if !failed[motor] && changes[motor] > f32::EPSILON {
+            motor_gain[motor] = mean / changes[motor];
+        }
+    }
+
+    info!("Calibrated motor min: {}", motor_min);
+    info!("Calibrated motor gain: {}", motor_gain);
+    info!("Calibrated motor direction: {}", motor_direction);
+
+    if apply && working < WHEELS.len() {
+        warn!("Not applying motor calibration, failed motors: {}", failed);
+    } else if apply {
+        set_config!(motor_min, motor_min);
+        set_config!(motor_gain, motor_gain);
+        set_config!(motor_direction, motor_direction);
+    }
+}
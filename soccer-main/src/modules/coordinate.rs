@@ -1,7 +1,10 @@
 use crate::{
+    config::get_config,
     constants::{FIELD_LENGTH, FIELD_WIDTH},
     hardware::{LidarData, LIDAR_SIGNAL},
-    modules::{COORDINATE_CHANGED, COORDINATE_MUTEX, HEADING_MUTEX, UNIGNORE_SIGNAL},
+    modules::{
+        COORDINATE_CHANGED, COORDINATE_MUTEX, HEADING_MUTEX, OBSTACLE_MUTEX, UNIGNORE_SIGNAL,
+    },
     utils::{debug::debug_variable, read_mutex, write_mutex},
 };
 use defmt::info;
@@ -13,6 +16,7 @@ const LIDAR_DIST_MIN: f32 = 20.;
 const LIDAR_SIGNAL_MIN: u16 = 200;
 const LIDAR_CHANGE_TOLERANCE: f32 = 5.;
 const LIDAR_IGNORE_TOLERANCE: i32 = 100;
+const LIDAR_OBSTACLE_TOLERANCE: f32 = 15.;
 
 const FIELD_LENGTH_TOLERANCE: f32 = 14.;
 const FIELD_WIDTH_TOLERANCE: f32 = 8.;
@@ -31,6 +35,8 @@ async fn coordinate_task() {
     let mut ignore_left = 0;
     let mut ignore_right = 0;
 
+    let mut mirrored = get_config!(mirrored);
+
     loop {
         let (left, right, front, back);
 
@@ -60,6 +66,15 @@ async fn coordinate_task() {
             }
         }
 
+        // after switching sides each sensor faces the other way, so its history moves with it
+        if get_config!(mirrored) != mirrored {
+            mirrored = !mirrored;
+            (last_front, last_back, last_left, last_right) =
+                (last_back, last_front, last_right, last_left);
+            (ignore_front, ignore_back, ignore_left, ignore_right) =
+                (ignore_back, ignore_front, ignore_right, ignore_left);
+        }
+
         let heading = read_mutex!(HEADING_MUTEX);
         let cos = heading.to_radians().cos().abs();
 
@@ -140,6 +155,7 @@ async fn coordinate_task() {
             debug_variable!("lidar ok", false);
             let (x, y, _) = read_mutex!(COORDINATE_MUTEX);
             write_mutex!(COORDINATE_MUTEX, (x, y, false));
+            write_mutex!(OBSTACLE_MUTEX, [None; 4]);
             publisher.publish_immediate(());
             continue;
         }
@@ -181,12 +197,27 @@ async fn coordinate_task() {
             last_right = right.0;
         }
 
+        let is_obstacle = |reading: (f32, u16), expected: f32| {
+            reading.0 >= LIDAR_DIST_MIN
+                && reading.1 > LIDAR_SIGNAL_MIN
+                && expected - reading.0 > LIDAR_OBSTACLE_TOLERANCE
+        };
+
+        let obstacles = [
+            is_obstacle(front, y).then_some((x, y - front.0)),
+            is_obstacle(left, x).then_some((x - left.0, y)),
+            is_obstacle(right, FIELD_WIDTH - x).then_some((x + right.0, y)),
+            is_obstacle(back, FIELD_LENGTH - y).then_some((x, y + back.0)),
+        ];
+
         write_mutex!(COORDINATE_MUTEX, (x, y, true));
+        write_mutex!(OBSTACLE_MUTEX, obstacles);
         publisher.publish_immediate(());
 
         debug_variable!("lidar ok", true);
         debug_variable!("lidar x", x);
         debug_variable!("lidar y", y);
+        debug_variable!("lidar obstacles", obstacles.iter().flatten().count());
     }
 }
 
@@ -0,0 +1,126 @@
+use crate::{
+    config::get_config,
+    constants::{
+        FIELD_LENGTH, FIELD_MARGIN, FIELD_WIDTH, PENALTY_DEPTH, PENALTY_WIDTH, ROBOT_RADIUS,
+    },
+    modules::{planning::Zone, COORDINATE_MUTEX, PENALTY_MUTEX},
+    utils::{debug::debug_variable, read_mutex, write_mutex},
+};
+use defmt::{info, warn};
+use embassy_executor::Spawner;
+use embassy_time::{Instant, Timer};
+use nalgebra::Vector2;
+use num_traits::Float;
+
+const PENALTY_CLEARANCE: f32 = 3.;
+const PENALTY_INTERVAL: u64 = 50;
+const PENALTY_LIMIT: u64 = 10000;
+const PENALTY_EXIT: u64 = 7000;
+const CENTER_RADIUS: f32 = 30.;
+
+// our own penalty area, in front of the goal we defend
+pub const PENALTY_AREA: Zone = Zone::new(
+    (FIELD_WIDTH - PENALTY_WIDTH) / 2.,
+    FIELD_LENGTH - FIELD_MARGIN - PENALTY_DEPTH,
+    (FIELD_WIDTH + PENALTY_WIDTH) / 2.,
+    FIELD_LENGTH - FIELD_MARGIN,
+);
+
+// whether any part of the robot is inside the penalty area
+pub fn in_penalty_area(x: f32, y: f32) -> bool {
+    let closest = PENALTY_AREA.closest(Vector2::new(x, y));
+    (closest - Vector2::new(x, y)).norm() < ROBOT_RADIUS
+}
+
+// moves a target to the nearest spot where the whole robot is clear of the penalty area
+pub fn keep_out(x: f32, y: f32) -> (f32, f32) {
+    let margin = ROBOT_RADIUS + PENALTY_CLEARANCE;
+    let (x1, y1, x2) = (
+        PENALTY_AREA.x1 - margin,
+        PENALTY_AREA.y1 - margin,
+        PENALTY_AREA.x2 + margin,
+    );
+
+    if x <= x1 || x >= x2 || y <= y1 {
+        return (x, y);
+    }
+
+    let candidates = [(x, y1), (x1, y), (x2, y)];
+    candidates
+        .into_iter()
+        .min_by(|a, b| {
+            let da = (a.0 - x).abs() + (a.1 - y).abs();
+            let db = (b.0 - x).abs() + (b.1 - y).abs();
+            da.total_cmp(&db)
+        })
+        .unwrap_or((x, y1))
+}
+
+// during the opponent's kickoff the robot has to stay in its own half and out of the centre circle
+pub fn keep_in_own_half(x: f32, y: f32) -> (f32, f32) {
+    let center = Vector2::new(FIELD_WIDTH / 2., FIELD_LENGTH / 2.);
+    let position = Vector2::new(x, y.max(half_line()));
+    let offset = position - center;
+    let min = CENTER_RADIUS + ROBOT_RADIUS;
+
+    let position = if offset.norm() < min {
+        center + offset.normalize() * min
+    } else {
+        position
+    };
+    (position.x, position.y)
+}
+
+// the furthest forward the robot can be while staying in its own half
+pub fn half_line() -> f32 {
+    FIELD_LENGTH / 2. + ROBOT_RADIUS
+}
+
+#[embassy_executor::task]
+async fn penalty_task() {
+    let mut entered: Option<Instant> = None;
+    let mut leaving = false;
+    let mut over = false;
+
+    loop {
+        Timer::after_millis(PENALTY_INTERVAL).await;
+
+        let (x, y, ok) = read_mutex!(COORDINATE_MUTEX);
+
+        // only the centre counts here, since the goalie waits just in front of the area with part
+        // of itself over the line
+        if !get_config!(started) || !ok || !PENALTY_AREA.contains(Vector2::new(x, y)) {
+            if entered.is_some() {
+                debug_variable!("penalty time", 0);
+            }
+            entered = None;
+            leaving = false;
+            over = false;
+            write_mutex!(PENALTY_MUTEX, (0, false));
+            continue;
+        }
+
+        let duration = entered
+            .get_or_insert_with(Instant::now)
+            .elapsed()
+            .as_millis();
+
+        if duration > PENALTY_EXIT && !leaving {
+            info!("In penalty area for {} ms, leaving", duration);
+            leaving = true;
+        }
+        if duration > PENALTY_LIMIT && !over {
+            over = true;
+            warn!("In penalty area for over {} ms", PENALTY_LIMIT);
+        }
+
+        write_mutex!(PENALTY_MUTEX, (duration, leaving));
+        debug_variable!("penalty time", duration);
+    }
+}
+
+pub async fn init(spawner: &Spawner) {
+    info!("Starting field");
+
+    spawner.must_spawn(penalty_task());
+}
@@ -0,0 +1,268 @@
+use crate::{
+    modules::{role::LINK_TIMEOUT, BALL_MUTEX, TEAMMATE_MUTEX},
+    utils::{self, construct_vector, debug::debug_variable, read_mutex, write_mutex},
+};
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, channel::Channel, mutex::Mutex};
+use embassy_time::{Instant, Timer};
+
+const GAME_INTERVAL: u64 = 100;
+const HALF_DURATION: u64 = 600000;
+const HALVES: u8 = 2;
+const KICKOFF_TIMEOUT: u64 = 10000;
+const KICKOFF_BALL_DISTANCE: f32 = 10.;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Phase {
+    Idle,
+    KickoffAttacking,
+    KickoffDefending,
+    Playing,
+    Paused,
+    Damaged,
+    HalfTime,
+}
+
+impl Phase {
+    const ALL: [Phase; 7] = [
+        Phase::Idle,
+        Phase::KickoffAttacking,
+        Phase::KickoffDefending,
+        Phase::Playing,
+        Phase::Paused,
+        Phase::Damaged,
+        Phase::HalfTime,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Idle => "idle",
+            Phase::KickoffAttacking => "kickoff_attacking",
+            Phase::KickoffDefending => "kickoff_defending",
+            Phase::Playing => "playing",
+            Phase::Paused => "paused",
+            Phase::Damaged => "damaged",
+            Phase::HalfTime => "half_time",
+        }
+    }
+
+    // whether the robot is allowed to move and the game clock runs
+    pub fn active(self) -> bool {
+        matches!(
+            self,
+            Phase::KickoffAttacking | Phase::KickoffDefending | Phase::Playing
+        )
+    }
+
+    pub fn kickoff(self) -> bool {
+        matches!(self, Phase::KickoffAttacking | Phase::KickoffDefending)
+    }
+
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.get(code as usize).copied()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Event {
+    // a short press of the button starts or pauses the game
+    Button,
+    // holding the button takes the robot out as damaged or brings it back
+    Hold,
+    Start,
+    Stop,
+    Pause,
+    Resume,
+    Kickoff(bool),
+    Damaged(bool),
+    HalfTime,
+    Sync(Phase),
+}
+
+impl Event {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "start" => Some(Event::Start),
+            "stop" => Some(Event::Stop),
+            "pause" => Some(Event::Pause),
+            "resume" => Some(Event::Resume),
+            "kickoff_attacking" => Some(Event::Kickoff(true)),
+            "kickoff_defending" => Some(Event::Kickoff(false)),
+            "damaged" => Some(Event::Damaged(true)),
+            "repaired" => Some(Event::Damaged(false)),
+            "half_time" => Some(Event::HalfTime),
+            _ => None,
+        }
+    }
+}
+
+// the clock only counts time spent in play and restarts with every half, and during a kickoff
+// the robots first get into position and only play once the kickoff has been taken
+#[derive(Clone, Copy)]
+pub struct Game {
+    pub phase: Phase,
+    pub clock: u64,
+    pub half: u8,
+    pub kicked_off: bool,
+}
+
+impl Game {
+    pub fn running(&self) -> bool {
+        self.phase.active() && (!self.phase.kickoff() || self.kicked_off)
+    }
+}
+
+pub static GAME_MUTEX: Mutex<CriticalSectionRawMutex, Game> = Mutex::new(Game {
+    phase: Phase::Idle,
+    clock: 0,
+    half: 1,
+    kicked_off: false,
+});
+pub static GAME_CHANNEL: Channel<CriticalSectionRawMutex, Event, 4> = Channel::new();
+
+fn next(phase: Phase, event: Event) -> Phase {
+    match (phase, event) {
+        (_, Event::Stop) => Phase::Idle,
+        // a damaged robot has to be brought back before anything else and then waits to resume
+        (Phase::Damaged, Event::Damaged(false) | Event::Hold) => Phase::Paused,
+        (Phase::Damaged, _) => Phase::Damaged,
+        (_, Event::Damaged(true) | Event::Hold) => Phase::Damaged,
+        (_, Event::Damaged(false)) => phase,
+        (_, Event::Sync(synced)) => synced,
+        (_, Event::Kickoff(true)) => Phase::KickoffAttacking,
+        (_, Event::Kickoff(false)) => Phase::KickoffDefending,
+        (_, Event::HalfTime) => Phase::HalfTime,
+        (
+            Phase::Idle | Phase::Paused | Phase::HalfTime,
+            Event::Start | Event::Resume | Event::Button,
+        ) => Phase::Playing,
+        (_, Event::Pause | Event::Button) => Phase::Paused,
+        (_, Event::Start | Event::Resume) => phase,
+    }
+}
+
+pub async fn send(event: Event) {
+    GAME_CHANNEL.send(event).await;
+}
+
+pub async fn phase() -> Phase {
+    read_mutex!(GAME_MUTEX).phase
+}
+
+#[embassy_executor::task]
+async fn game_task() {
+    let mut game = read_mutex!(GAME_MUTEX);
+    let mut last_tick = Instant::now();
+    let mut phase_start = Instant::now();
+    let mut kickoff_ball: Option<(f32, f32)> = None;
+    let mut teammate_phase: Option<Phase> = None;
+
+    loop {
+        let event = select(GAME_CHANNEL.receive(), Timer::after_millis(GAME_INTERVAL)).await;
+
+        let now = Instant::now();
+        if game.running() {
+            game.clock += (now - last_tick).as_millis();
+        }
+        last_tick = now;
+
+        // the start signal during a kickoff lets the robots leave their kickoff positions
+        let waiting = game.phase.kickoff() && !game.kicked_off;
+        let mut kick = false;
+
+        let mut phase = match event {
+            Either::First(Event::Start | Event::Resume | Event::Button) if waiting => {
+                kick = true;
+                game.phase
+            }
+            Either::First(event) => next(game.phase, event),
+            Either::Second(_) => game.phase,
+        };
+
+        // follow phase changes made on the teammate so both robots kick off and pause together,
+        // except for it being taken out or brought back as damaged
+        let teammate = read_mutex!(TEAMMATE_MUTEX)
+            .filter(|teammate| teammate.last_seen.elapsed().as_millis() < LINK_TIMEOUT);
+        if let Some(teammate) = teammate {
+            let changed = teammate_phase
+                .is_some_and(|previous| previous != teammate.phase && previous != Phase::Damaged);
+            if changed && teammate.phase != Phase::Damaged {
+                phase = next(phase, Event::Sync(teammate.phase));
+            }
+            if waiting && teammate.phase == game.phase && teammate.kicked_off {
+                kick = true;
+            }
+        }
+        teammate_phase = teammate.map(|teammate| teammate.phase);
+
+        if kick && phase == game.phase {
+            info!("Kickoff taken at {} ms", game.clock);
+            game.kicked_off = true;
+            phase_start = Instant::now();
+        }
+
+        // the kickoff is over once the ball has been played or the kicking team ran out of time
+        if phase.kickoff() && phase == game.phase && game.kicked_off {
+            let (bx, by, bok) = read_mutex!(BALL_MUTEX);
+            if bok {
+                let (kx, ky) = *kickoff_ball.get_or_insert((bx, by));
+                let (distance, _) = construct_vector(bx - kx, by - ky);
+                if distance > KICKOFF_BALL_DISTANCE {
+                    phase = Phase::Playing;
+                }
+            }
+            if phase_start.elapsed().as_millis() > KICKOFF_TIMEOUT {
+                phase = Phase::Playing;
+            }
+        }
+
+        if phase == Phase::Playing && game.clock >= HALF_DURATION {
+            game.clock = 0;
+            if game.half < HALVES {
+                info!("End of half {}", game.half);
+                game.half += 1;
+                phase = Phase::HalfTime;
+            } else {
+                info!("End of game");
+                game.half = 1;
+                phase = Phase::Idle;
+            }
+        }
+
+        if phase != game.phase {
+            info!(
+                "Game {} -> {} at {} ms",
+                game.phase.name(),
+                phase.name(),
+                game.clock
+            );
+
+            if phase.active() && !game.phase.active() {
+                utils::start().await;
+            } else if !phase.active() && game.phase.active() {
+                utils::stop().await;
+            }
+
+            game.phase = phase;
+            game.kicked_off = false;
+            phase_start = Instant::now();
+            kickoff_ball = None;
+        }
+
+        write_mutex!(GAME_MUTEX, game);
+        debug_variable!("game phase", game.phase.name());
+        debug_variable!("game clock", game.clock / 1000);
+    }
+}
+
+pub async fn init(spawner: &Spawner) {
+    info!("Starting game");
+
+    spawner.must_spawn(game_task());
+}
@@ -1,6 +1,6 @@
 use crate::{
     hardware::{ImuData, IMU_SIGNAL},
-    modules::{HEADING_CHANGED, HEADING_MUTEX},
+    modules::{side, HEADING_CHANGED, HEADING_MUTEX, HEADING_RATE_MUTEX},
     utils::{debug::debug_variable, write_mutex},
 };
 use defmt::info;
@@ -11,12 +11,22 @@ async fn heading_task() {
     let publisher = HEADING_CHANGED.immediate_publisher();
 
     loop {
-        let ImuData { angle } = IMU_SIGNAL.wait().await;
+        let ImuData { angle, rate } = IMU_SIGNAL.wait().await;
+
+        // turning the heading around is all it takes to switch sides, as the lidar readings are
+        // mapped onto the field by heading
+        let angle = if side::mirrored().await {
+            side::mirror_heading(angle)
+        } else {
+            angle
+        };
 
         write_mutex!(HEADING_MUTEX, angle);
+        write_mutex!(HEADING_RATE_MUTEX, rate);
         publisher.publish_immediate(());
 
         debug_variable!("heading", angle);
+        debug_variable!("heading rate", rate);
     }
 }
 
@@ -0,0 +1,89 @@
+use crate::hardware::MOTOR_COUNT;
+use nalgebra::{SMatrix, SVector};
+use num_traits::Float;
+
+// every wheel has its own motor, so a different base only needs its wheels listed here and the motor
+// count and motor pins changed to match
+pub const WHEEL_COUNT: usize = MOTOR_COUNT;
+
+pub struct Wheel {
+    pub motor: usize,
+    pub angle: f32,
+    pub distance: f32,
+    pub radius: f32,
+}
+
+// velocities are in cm/s to the right and to the front, angular velocity is in rad/s clockwise, and
+// wheel speeds are in rad/s
+
+// angle is the direction (clockwise from the front, in degrees) the robot is pushed in when the
+// wheel spins forwards, and forward spins on every wheel turn the robot clockwise, with motors that
+// are wired backwards flipped by the motor_direction config
+pub const WHEELS: [Wheel; WHEEL_COUNT] = [
+    Wheel {
+        motor: 0,
+        angle: -45.,
+        distance: 8.,
+        radius: 2.5,
+    },
+    Wheel {
+        motor: 1,
+        angle: -135.,
+        distance: 8.,
+        radius: 2.5,
+    },
+    Wheel {
+        motor: 2,
+        angle: 45.,
+        distance: 8.,
+        radius: 2.5,
+    },
+    Wheel {
+        motor: 3,
+        angle: 135.,
+        distance: 8.,
+        radius: 2.5,
+    },
+];
+
+fn jacobian() -> SMatrix<f32, WHEEL_COUNT, 3> {
+    SMatrix::from_fn(|row, col| {
+        let wheel = &WHEELS[row];
+        let (sin, cos) = wheel.angle.to_radians().sin_cos();
+        match col {
+            0 => sin,
+            1 => cos,
+            _ => wheel.distance,
+        }
+    })
+}
+
+pub fn inverse(vx: f32, vy: f32, omega: f32) -> [f32; WHEEL_COUNT] {
+    let speeds = jacobian() * SVector::<f32, 3>::new(vx, vy, omega);
+
+    let mut wheels = [0.; WHEEL_COUNT];
+    for (index, wheel) in WHEELS.iter().enumerate() {
+        wheels[index] = speeds[index] / wheel.radius;
+    }
+
+    wheels
+}
+
+// the least squares body velocity for the given wheel speeds, in whatever units they are given in
+pub fn forward(wheels: &[f32; WHEEL_COUNT]) -> (f32, f32, f32) {
+    let speeds = SVector::<f32, WHEEL_COUNT>::from_fn(|index, _| {
+        let wheel = &WHEELS[index];
+        wheels[index] * wheel.radius
+    });
+
+    let jacobian = jacobian();
+    let transpose = jacobian.transpose();
+
+    match (transpose * jacobian).try_inverse() {
+        Some(inverse) => {
+            let velocity = inverse * transpose * speeds;
+            (velocity.x, velocity.y, velocity.z)
+        }
+        None => (0., 0., 0.),
+    }
+}
@@ -2,22 +2,96 @@ use embassy_sync::{
     blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex, pubsub::PubSubChannel,
     signal::Signal,
 };
+use embassy_time::Instant;
+use game::Phase;
 
+pub mod aiming;
 pub mod ball;
+#[cfg(feature = "network")]
+pub mod calibration;
 pub mod coordinate;
+pub mod field;
+pub mod game;
 pub mod heading;
+pub mod kinematics;
 pub mod movement;
+pub mod orbit;
+pub mod planning;
+pub mod role;
+pub mod side;
+pub mod stuck;
+pub mod trajectory;
+#[cfg(feature = "network")]
+pub mod tuning;
+pub mod vm;
+pub mod world;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Frame {
+    Field,
+    Robot,
+}
+
+// speeds are from -1 to 1, the field frame follows field coordinates while the robot frame has x
+// to the right and y to the front, and omega overrides the heading controller when set
+#[derive(Clone, Copy, PartialEq)]
+pub struct Velocity {
+    pub x: f32,
+    pub y: f32,
+    pub omega: Option<f32>,
+    pub frame: Frame,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Role {
+    Striker,
+    Goalie,
+}
+
+// the latest state broadcast by the other robot, where cost is how badly placed it is to attack
+// and the ball comes with when it was last seen and how much it can be trusted, along with the
+// phase of the game it is in
+#[derive(Clone, Copy)]
+pub struct Teammate {
+    pub id: u8,
+    pub role: Role,
+    pub position: (f32, f32, bool),
+    pub heading: f32,
+    pub ball: (f32, f32, bool),
+    pub ball_seen: Instant,
+    pub ball_confidence: f32,
+    pub started: bool,
+    pub phase: Phase,
+    pub kicked_off: bool,
+    pub mirrored: bool,
+    pub stuck: bool,
+    pub cost: f32,
+    pub last_seen: Instant,
+}
 
 pub static HEADING_MUTEX: Mutex<CriticalSectionRawMutex, f32> = Mutex::new(0.);
+pub static HEADING_RATE_MUTEX: Mutex<CriticalSectionRawMutex, f32> = Mutex::new(0.);
 pub static COORDINATE_MUTEX: Mutex<CriticalSectionRawMutex, (f32, f32, bool)> =
     Mutex::new((0., 0., false));
 pub static BALL_MUTEX: Mutex<CriticalSectionRawMutex, (f32, f32, bool)> =
     Mutex::new((0., 0., false));
+pub static BALL_SEEN_MUTEX: Mutex<CriticalSectionRawMutex, Instant> =
+    Mutex::new(Instant::from_ticks(0));
+pub static BALL_VELOCITY_MUTEX: Mutex<CriticalSectionRawMutex, (f32, f32)> = Mutex::new((0., 0.));
 pub static GOAL_MUTEX: Mutex<CriticalSectionRawMutex, (f32, f32, bool)> =
     Mutex::new((0., 0., false));
+pub static STUCK_MUTEX: Mutex<CriticalSectionRawMutex, bool> = Mutex::new(false);
+pub static TRAJECTORY_MUTEX: Mutex<CriticalSectionRawMutex, bool> = Mutex::new(false);
+pub static PENALTY_MUTEX: Mutex<CriticalSectionRawMutex, (u64, bool)> = Mutex::new((0, false));
+pub static CLEARING_MUTEX: Mutex<CriticalSectionRawMutex, bool> = Mutex::new(false);
+pub static ROLE_MUTEX: Mutex<CriticalSectionRawMutex, Role> = Mutex::new(Role::Striker);
+pub static TEAMMATE_MUTEX: Mutex<CriticalSectionRawMutex, Option<Teammate>> = Mutex::new(None);
+pub static OBSTACLE_MUTEX: Mutex<CriticalSectionRawMutex, [Option<(f32, f32)>; 4]> =
+    Mutex::new([None; 4]);
 
 pub static HEADING_SIGNAL: Signal<CriticalSectionRawMutex, f32> = Signal::new();
 pub static COORDINATE_SIGNAL: Signal<CriticalSectionRawMutex, (f32, f32)> = Signal::new();
+pub static VELOCITY_SIGNAL: Signal<CriticalSectionRawMutex, Velocity> = Signal::new();
 pub static UNIGNORE_SIGNAL: Signal<CriticalSectionRawMutex, (bool, bool, bool, bool)> =
     Signal::new();
 
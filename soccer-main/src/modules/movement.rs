@@ -1,88 +1,405 @@
 use crate::{
     config::get_config,
     constants::{FIELD_LENGTH, FIELD_MARGIN, FIELD_MARGIN_X, FIELD_MARGIN_Y, FIELD_WIDTH},
-    hardware::{MotorData, MOTOR_SIGNAL},
+    hardware::{MotorData, MotorMode, MOTOR_COUNT, MOTOR_SIGNAL},
     modules::{
-        COORDINATE_CHANGED, COORDINATE_MUTEX, COORDINATE_SIGNAL, HEADING_CHANGED, HEADING_MUTEX,
-        HEADING_SIGNAL,
+        field, game,
+        game::Phase,
+        kinematics,
+        kinematics::{WHEELS, WHEEL_COUNT},
+        planning, Frame, Role, Velocity, BALL_MUTEX, CLEARING_MUTEX, COORDINATE_CHANGED,
+        COORDINATE_MUTEX, COORDINATE_SIGNAL, HEADING_CHANGED, HEADING_MUTEX, HEADING_RATE_MUTEX,
+        HEADING_SIGNAL, OBSTACLE_MUTEX, PENALTY_MUTEX, ROLE_MUTEX, VELOCITY_SIGNAL,
     },
-    utils::{clamp_angle, construct_vector, debug::debug_variable, read_mutex},
+    params::PARAMS,
+    utils::{clamp_angle, construct_vector, debug::debug_variable, read_mutex, write_mutex},
 };
 use defmt::info;
 use embassy_executor::Spawner;
 use embassy_futures::select::{select, Either};
-use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex, signal::Signal};
+use embassy_time::Instant;
 use num_traits::Float;
 use pid::Pid;
 
-const MOTOR_MIN: f32 = 26.;
-const MOTOR_MIN_FINAL: f32 = 27.;
+const MOTOR_MIN_MARGIN: f32 = 1.;
+const MOTOR_MAX: f32 = 254.;
 const MOTOR_ANGLE_RATIO: f32 = 0.2;
 const MOTOR_POSITION_RATIO: f32 = 0.8;
 const NO_COORDINATE_MAX: f32 = 0.5;
 
-const STRIKER_DISTANCE: f32 = 30.;
+const BRAKE_BALL_DISTANCE: f32 = 20.;
+const BRAKE_LINE_DISTANCE: f32 = 10.;
+
+const VELOCITY_FILTER: f32 = 0.3;
+const REACTION_TIME: f32 = 0.1;
 
 pub static SPEED_ANGLE_SIGNAL: Signal<CriticalSectionRawMutex, (f32, f32)> = Signal::new();
 pub static ROTATION_SIGNAL: Signal<CriticalSectionRawMutex, f32> = Signal::new();
 
-pub fn drive(speed: f32, angle: f32, rotation: f32) {
-    let angle = clamp_angle(45. - angle).to_radians();
-    let speed = speed * MOTOR_POSITION_RATIO * (255. - MOTOR_MIN);
+pub static MOTION_MUTEX: Mutex<CriticalSectionRawMutex, (f32, f32, f32)> = Mutex::new((0., 0., 0.));
+
+static OMEGA_MUTEX: Mutex<CriticalSectionRawMutex, Option<f32>> = Mutex::new(None);
+static BRAKE_MUTEX: Mutex<CriticalSectionRawMutex, bool> = Mutex::new(false);
+
+// returns the body velocity the wheel commands amount to, scaled like the speed and rotation given
+// rather than measured, since the motors have no encoders
+pub async fn drive(speed: f32, angle: f32, rotation: f32) -> (f32, f32, f32) {
+    write_mutex!(MOTION_MUTEX, (speed, angle, rotation));
+
+    let (sin, cos) = angle.to_radians().sin_cos();
 
-    let (sin, cos) = angle.sin_cos();
-    let max = sin.abs().max(cos.abs());
+    let translation = kinematics::inverse(sin, cos, 0.);
+    let spin = kinematics::inverse(0., 0., 1.);
 
-    let speed_x = speed * cos / max;
-    let speed_y = speed * sin / max;
+    let translation_max = translation
+        .iter()
+        .fold(f32::EPSILON, |acc, x| acc.max(x.abs()));
+    let spin_max = spin.iter().fold(f32::EPSILON, |acc, x| acc.max(x.abs()));
 
-    let mut speed_fl = speed_y;
-    let mut speed_fr = -speed_x;
-    let mut speed_bl = speed_x;
-    let mut speed_br = -speed_y;
+    let speed = speed * MOTOR_POSITION_RATIO;
+    let rotation = rotation * MOTOR_ANGLE_RATIO;
 
-    speed_fl += rotation * MOTOR_ANGLE_RATIO * (255. - MOTOR_MIN);
-    speed_fr += rotation * MOTOR_ANGLE_RATIO * (255. - MOTOR_MIN);
-    speed_bl += rotation * MOTOR_ANGLE_RATIO * (255. - MOTOR_MIN);
-    speed_br += rotation * MOTOR_ANGLE_RATIO * (255. - MOTOR_MIN);
+    let motor_min = get_config!(motor_min);
+    let motor_gain = get_config!(motor_gain);
+    let motor_direction = get_config!(motor_direction);
 
-    speed_fl += speed_fl.signum() * MOTOR_MIN;
-    speed_fr += speed_fr.signum() * MOTOR_MIN;
-    speed_bl += speed_bl.signum() * MOTOR_MIN;
-    speed_br += speed_br.signum() * MOTOR_MIN;
+    // idle wheels are shorted instead of left to roll when a stop has to be sharp
+    let idle = if read_mutex!(BRAKE_MUTEX) {
+        MotorMode::Brake
+    } else {
+        MotorMode::Coast
+    };
 
-    if speed_fl.abs() < MOTOR_MIN_FINAL {
-        speed_fl = 0.;
+    let mut translations = [0.; WHEEL_COUNT];
+    let mut rotations = [0.; WHEEL_COUNT];
+    let mut limits = [0.; WHEEL_COUNT];
+
+    for (index, wheel) in WHEELS.iter().enumerate() {
+        let motor = wheel.motor;
+
+        translations[index] = translation[index] / translation_max * speed;
+        rotations[index] = spin[index] / spin_max * rotation;
+        limits[index] = (MOTOR_MAX - motor_min[motor])
+            / (motor_gain[motor] * (255. - motor_min[motor])).max(f32::EPSILON);
     }
 
-    if speed_fr.abs() < MOTOR_MIN_FINAL {
-        speed_fr = 0.;
+    let (translation_scale, rotation_scale) = desaturate(
+        &translations,
+        &rotations,
+        &limits,
+        get_config!(rotation_priority),
+    );
+    debug_variable!(
+        "drive saturation",
+        1. - translation_scale.min(rotation_scale)
+    );
+
+    let mut wheels = [0.; WHEEL_COUNT];
+    let mut speeds = [0; MOTOR_COUNT];
+    let mut modes = [idle; MOTOR_COUNT];
+
+    for (index, wheel) in WHEELS.iter().enumerate() {
+        let motor = wheel.motor;
+
+        let wheel_speed =
+            translations[index] * translation_scale + rotations[index] * rotation_scale;
+        let mut motor_speed = wheel_speed * motor_gain[motor] * (255. - motor_min[motor]);
+
+        if motor_speed.abs() < MOTOR_MIN_MARGIN {
+            continue;
+        }
+
+        motor_speed += motor_speed.signum() * motor_min[motor];
+        motor_speed = motor_speed.clamp(-MOTOR_MAX, MOTOR_MAX);
+
+        wheels[index] = wheel_speed;
+        speeds[motor] = (motor_speed * motor_direction[motor]).round() as i16;
+        modes[motor] = MotorMode::Drive;
     }
 
-    if speed_bl.abs() < MOTOR_MIN_FINAL {
-        speed_bl = 0.;
+    MOTOR_SIGNAL.signal(MotorData { speeds, modes });
+
+    kinematics::forward(&wheels)
+}
+
+// returns the scale for translation and rotation so that every wheel stays within its limit
+fn desaturate(
+    translations: &[f32; WHEEL_COUNT],
+    rotations: &[f32; WHEEL_COUNT],
+    limits: &[f32; WHEEL_COUNT],
+    priority: f32,
+) -> (f32, f32) {
+    // scaling both together keeps the ratio between translation and rotation
+    let uniform = (0..WHEEL_COUNT).fold(1., |acc: f32, i| {
+        let total = (translations[i] + rotations[i]).abs();
+        if total > limits[i] {
+            acc.min(limits[i] / total)
+        } else {
+            acc
+        }
+    });
+
+    // otherwise keep as much rotation as possible and fit translation into the headroom
+    let rotation = (0..WHEEL_COUNT).fold(1., |acc: f32, i| {
+        let total = rotations[i].abs();
+        if total > limits[i] {
+            acc.min(limits[i] / total)
+        } else {
+            acc
+        }
+    });
+    let translation = (0..WHEEL_COUNT).fold(1., |acc: f32, i| {
+        let total = translations[i].abs();
+        if total < f32::EPSILON {
+            return acc;
+        }
+        let headroom = limits[i] - rotation * rotations[i] * translations[i].signum();
+        acc.min((headroom / total).max(0.))
+    });
+
+    let priority = priority.clamp(0., 1.);
+    (
+        priority * translation + (1. - priority) * uniform,
+        priority * rotation + (1. - priority) * uniform,
+    )
+}
+
+pub async fn brake() {
+    write_mutex!(BRAKE_MUTEX, true);
+    drive(0., 0., 0.).await;
+}
+
+async fn should_brake() -> bool {
+    let (x, y, ok) = read_mutex!(COORDINATE_MUTEX);
+    if !ok {
+        return false;
     }
 
-    if speed_br.abs() < MOTOR_MIN_FINAL {
-        speed_br = 0.;
+    let near_line = x < FIELD_MARGIN + BRAKE_LINE_DISTANCE
+        || x > FIELD_WIDTH - FIELD_MARGIN - BRAKE_LINE_DISTANCE
+        || y < FIELD_MARGIN + BRAKE_LINE_DISTANCE
+        || y > FIELD_LENGTH - FIELD_MARGIN - BRAKE_LINE_DISTANCE;
+
+    let (bx, by, ball_ok) = read_mutex!(BALL_MUTEX);
+    let (ball_distance, _) = construct_vector(bx - x, y - by);
+    let near_ball = ball_ok && ball_distance < BRAKE_BALL_DISTANCE;
+
+    near_line || near_ball
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Command {
+    Position((f32, f32)),
+    Velocity(Velocity),
+}
+
+async fn next_command() -> Command {
+    match select(COORDINATE_SIGNAL.wait(), VELOCITY_SIGNAL.wait()).await {
+        Either::First(data) => Command::Position(data),
+        Either::Second(data) => Command::Velocity(data),
     }
+}
 
-    MOTOR_SIGNAL.signal(MotorData {
-        fl: speed_fl.round() as i16,
-        fr: speed_fr.round() as i16,
-        bl: speed_bl.round() as i16,
-        br: speed_br.round() as i16,
-    });
+// the striker stays the same distance from our goal as the strategy selector assumes, and the goalie
+// may go as deep as the penalty area while clearing a ball out of it, so that it can get behind a
+// ball sitting in front of the goal
+async fn field_bounds(x: f32, goalie: bool) -> (f32, f32, f32, f32) {
+    let clearing = goalie && read_mutex!(CLEARING_MUTEX);
+    let striker_distance = read_mutex!(PARAMS).striker_distance;
+
+    if x > FIELD_MARGIN_X && x < FIELD_WIDTH - FIELD_MARGIN_X && !clearing {
+        let goalie_y = if !goalie {
+            FIELD_LENGTH - FIELD_MARGIN_Y - striker_distance
+        } else {
+            FIELD_LENGTH - FIELD_MARGIN_Y
+        };
+        (
+            FIELD_MARGIN,
+            FIELD_WIDTH - FIELD_MARGIN,
+            FIELD_MARGIN_Y,
+            goalie_y,
+        )
+    } else {
+        let goalie_y = if !goalie {
+            FIELD_LENGTH - FIELD_MARGIN_Y - striker_distance
+        } else {
+            FIELD_LENGTH - FIELD_MARGIN
+        };
+        (
+            FIELD_MARGIN,
+            FIELD_WIDTH - FIELD_MARGIN,
+            FIELD_MARGIN,
+            goalie_y,
+        )
+    }
+}
+
+// the striker never enters our penalty area and the goalie has to leave it once it has been in
+// there for too long
+async fn keep_out_of_penalty(goalie: bool) -> bool {
+    !goalie || read_mutex!(PENALTY_MUTEX).1
+}
+
+async fn position_control(target: (f32, f32), pid: &mut Pid<f32>) -> (f32, f32) {
+    let heading = read_mutex!(HEADING_MUTEX);
+    let (x, y, ok) = read_mutex!(COORDINATE_MUTEX);
+
+    let goalie = read_mutex!(ROLE_MUTEX) == Role::Goalie;
+
+    let (tx, ty) = if ok {
+        let (min_x, max_x, min_y, max_y) = field_bounds(target.0, goalie).await;
+        let (tx, ty) = (target.0.clamp(min_x, max_x), target.1.clamp(min_y, max_y));
+        let (tx, ty) = if keep_out_of_penalty(goalie).await {
+            field::keep_out(tx, ty)
+        } else {
+            (tx, ty)
+        };
+        if game::phase().await == Phase::KickoffDefending {
+            field::keep_in_own_half(tx, ty)
+        } else {
+            (tx, ty)
+        }
+    } else {
+        (target.0, target.1)
+    };
+
+    debug_variable!("target x", tx);
+    debug_variable!("target y", ty);
+
+    let (distance, _) = construct_vector(tx - x, y - ty);
+
+    let (x_diff, y_diff) = if ok && get_config!(planning) {
+        let ball = read_mutex!(BALL_MUTEX);
+        let obstacles = read_mutex!(OBSTACLE_MUTEX);
+        planning::plan((x, y), (tx, ty), ball, &obstacles)
+    } else {
+        (tx - x, ty - y)
+    };
+
+    let (_, angle) = construct_vector(x_diff, -y_diff);
+    let angle = clamp_angle(angle.to_degrees() - heading);
+    let mut speed = -pid.next_control_output(distance).output;
+
+    if !ok {
+        speed = speed.min(NO_COORDINATE_MAX);
+    }
+
+    (speed, angle)
+}
+
+async fn velocity_control(velocity: Velocity) -> (f32, f32) {
+    let heading = read_mutex!(HEADING_MUTEX);
+    let (x, y, ok) = read_mutex!(COORDINATE_MUTEX);
+
+    let (mut vx, mut vy) = match velocity.frame {
+        Frame::Field => (velocity.x, velocity.y),
+        Frame::Robot => {
+            let (magnitude, angle) = construct_vector(velocity.x, velocity.y);
+            let (sin, cos) = (angle + heading.to_radians()).sin_cos();
+            (magnitude * sin, -magnitude * cos)
+        }
+    };
+
+    if ok {
+        let goalie = read_mutex!(ROLE_MUTEX) == Role::Goalie;
+        let (min_x, max_x, min_y, max_y) = field_bounds(x, goalie).await;
+        if (x <= min_x && vx < 0.) || (x >= max_x && vx > 0.) {
+            vx = 0.;
+        }
+        if (y <= min_y && vy < 0.) || (y >= max_y && vy > 0.) {
+            vy = 0.;
+        }
+        if vy > 0. && field::in_penalty_area(x, y) && keep_out_of_penalty(goalie).await {
+            vy = 0.;
+        }
+        if vy < 0. && y <= field::half_line() && game::phase().await == Phase::KickoffDefending {
+            vy = 0.;
+        }
+    }
+
+    let (speed, angle) = construct_vector(vx, -vy);
+    let angle = clamp_angle(angle.to_degrees() - heading);
+    let mut speed = speed.min(1.);
+
+    if !ok {
+        speed = speed.min(NO_COORDINATE_MAX);
+    }
+
+    (speed, angle)
+}
+
+#[derive(Default)]
+struct VelocityEstimate {
+    last: Option<(f32, f32, Instant)>,
+    vx: f32,
+    vy: f32,
+}
+
+impl VelocityEstimate {
+    // field velocity in cm/s from successive coordinates, low pass filtered
+    fn update(&mut self, x: f32, y: f32, ok: bool) {
+        if !ok {
+            *self = Self::default();
+            return;
+        }
+
+        if let Some((last_x, last_y, last_time)) = self.last {
+            let dt = last_time.elapsed().as_micros() as f32 / 1000000.;
+            if dt > f32::EPSILON {
+                self.vx += ((x - last_x) / dt - self.vx) * VELOCITY_FILTER;
+                self.vy += ((y - last_y) / dt - self.vy) * VELOCITY_FILTER;
+            }
+        }
+
+        self.last = Some((x, y, Instant::now()));
+    }
+}
+
+// the speed towards a wall from which the robot can still stop before reaching it
+fn stopping_speed(distance: f32, approach: f32, braking_distance: f32) -> f32 {
+    let distance = distance - approach.max(0.) * REACTION_TIME;
+    (distance.max(0.) / braking_distance).sqrt().min(1.)
+}
+
+async fn limit_to_walls(speed: f32, angle: f32, estimate: &VelocityEstimate) -> (f32, f32) {
+    let braking_distance = get_config!(braking_distance);
+    let (x, y, ok) = read_mutex!(COORDINATE_MUTEX);
+
+    if !ok || braking_distance <= 0. {
+        return (speed, angle);
+    }
+
+    let heading = read_mutex!(HEADING_MUTEX);
+    let (sin, cos) = (angle + heading).to_radians().sin_cos();
+    let (vx, vy) = (speed * sin, -speed * cos);
+
+    let (min_x, max_x, min_y, max_y) =
+        field_bounds(x, read_mutex!(ROLE_MUTEX) == Role::Goalie).await;
+    let vx = vx.clamp(
+        -stopping_speed(x - min_x, -estimate.vx, braking_distance),
+        stopping_speed(max_x - x, estimate.vx, braking_distance),
+    );
+    let vy = vy.clamp(
+        -stopping_speed(y - min_y, -estimate.vy, braking_distance),
+        stopping_speed(max_y - y, estimate.vy, braking_distance),
+    );
+
+    let (limited, angle) = construct_vector(vx, -vy);
+    debug_variable!("wall limit", limited / speed.abs().max(f32::EPSILON));
+
+    (limited, clamp_angle(angle.to_degrees() - heading))
 }
 
 #[embassy_executor::task]
 async fn speed_angle_task() {
-    let mut target = COORDINATE_SIGNAL.wait().await;
+    let mut command = next_command().await;
     let mut subscriber = COORDINATE_CHANGED.subscriber().unwrap();
+    let mut estimate = VelocityEstimate::default();
 
     loop {
         if !get_config!(started) {
-            target = COORDINATE_SIGNAL.wait().await;
+            command = next_command().await;
             if !get_config!(started) {
                 continue;
             }
@@ -91,57 +408,38 @@ async fn speed_angle_task() {
         let mut pid = Pid::new(0., 1.);
         pid.p(get_config!(pid2_p), 1.).d(get_config!(pid2_d), 1.);
 
+        match command {
+            Command::Position(_) => {
+                write_mutex!(OMEGA_MUTEX, None);
+                debug_variable!("movement mode", "position");
+            }
+            Command::Velocity(velocity) => {
+                write_mutex!(OMEGA_MUTEX, velocity.omega);
+                debug_variable!("movement mode", "velocity");
+            }
+        }
+
         loop {
-            match select(COORDINATE_SIGNAL.wait(), subscriber.next_message()).await {
+            match select(next_command(), subscriber.next_message()).await {
                 Either::First(data) => {
-                    if target != data || !get_config!(started) {
-                        target = data;
+                    if command != data || !get_config!(started) {
+                        command = data;
                         break;
                     }
                 }
                 Either::Second(_) => {
-                    let heading = read_mutex!(HEADING_MUTEX);
                     let (x, y, ok) = read_mutex!(COORDINATE_MUTEX);
+                    estimate.update(x, y, ok);
 
-                    let goalie = get_config!(goalie);
-
-                    let (tx, ty) = if ok {
-                        if target.0 > FIELD_MARGIN_X && target.0 < FIELD_WIDTH - FIELD_MARGIN_X {
-                            let goalie_y = if !goalie {
-                                FIELD_LENGTH - FIELD_MARGIN_Y - STRIKER_DISTANCE
-                            } else {
-                                FIELD_LENGTH - FIELD_MARGIN_Y
-                            };
-                            (
-                                target.0.clamp(FIELD_MARGIN, FIELD_WIDTH - FIELD_MARGIN),
-                                target.1.clamp(FIELD_MARGIN_Y, goalie_y),
-                            )
-                        } else {
-                            let goalie_y = if !goalie {
-                                FIELD_LENGTH - FIELD_MARGIN_Y - STRIKER_DISTANCE
-                            } else {
-                                FIELD_LENGTH - FIELD_MARGIN
-                            };
-                            (
-                                target.0.clamp(FIELD_MARGIN, FIELD_WIDTH - FIELD_MARGIN),
-                                target.1.clamp(FIELD_MARGIN, goalie_y),
-                            )
-                        }
-                    } else {
-                        (target.0, target.1)
+                    let (speed, angle) = match command {
+                        Command::Position(target) => position_control(target, &mut pid).await,
+                        Command::Velocity(velocity) => velocity_control(velocity).await,
                     };
+                    let (speed, angle) = limit_to_walls(speed, angle, &estimate).await;
 
-                    debug_variable!("target x", tx);
-                    debug_variable!("target y", ty);
-
-                    let (x_diff, y_diff) = (tx - x, y - ty);
-                    let (distance, angle) = construct_vector(x_diff, y_diff);
-                    let angle = clamp_angle(angle.to_degrees() - heading);
-                    let mut speed = -pid.next_control_output(distance).output;
-
-                    if !ok {
-                        speed = speed.min(NO_COORDINATE_MAX);
-                    }
+                    let brake = should_brake().await;
+                    write_mutex!(BRAKE_MUTEX, brake);
+                    debug_variable!("movement brake", brake);
 
                     SPEED_ANGLE_SIGNAL.signal((speed, angle));
                     debug_variable!("pid speed", speed);
@@ -152,6 +450,50 @@ async fn speed_angle_task() {
     }
 }
 
+struct HeadingPid {
+    p: f32,
+    i: f32,
+    d: f32,
+    i_max: f32,
+    integral: f32,
+    last_update: Option<Instant>,
+}
+
+impl HeadingPid {
+    async fn new() -> Self {
+        Self {
+            p: get_config!(pid_p),
+            i: get_config!(pid_i),
+            d: get_config!(pid_d),
+            i_max: get_config!(pid_i_max),
+            integral: 0.,
+            last_update: None,
+        }
+    }
+
+    fn next(&mut self, target: f32, heading: f32, rate: f32) -> f32 {
+        let dt = self
+            .last_update
+            .map(|last| last.elapsed().as_micros() as f32 / 1000000.)
+            .unwrap_or(0.);
+        self.last_update = Some(Instant::now());
+
+        // the error is wrapped, and the derivative uses the gyro rate so it never sees the wrap
+        let error = clamp_angle(target - heading);
+
+        let proportional = self.p * error;
+        let derivative = -self.d * rate * dt;
+        let output = proportional + self.integral + derivative;
+
+        // stop integrating while saturated in the direction of the error
+        if output.abs() < 1. || output.signum() != error.signum() {
+            self.integral = (self.integral + self.i * error).clamp(-self.i_max, self.i_max);
+        }
+
+        (proportional + self.integral + derivative).clamp(-1., 1.)
+    }
+}
+
 #[embassy_executor::task]
 async fn rotation_task() {
     let mut target = HEADING_SIGNAL.wait().await;
@@ -165,8 +507,7 @@ async fn rotation_task() {
             }
         }
 
-        let mut pid = Pid::new(0., 1.);
-        pid.p(get_config!(pid_p), 1.).d(get_config!(pid_d), 1.);
+        let mut pid = HeadingPid::new().await;
 
         loop {
             match select(HEADING_SIGNAL.wait(), subscriber.next_message()).await {
@@ -177,9 +518,15 @@ async fn rotation_task() {
                     }
                 }
                 Either::Second(_) => {
+                    if let Some(omega) = read_mutex!(OMEGA_MUTEX) {
+                        ROTATION_SIGNAL.signal(omega);
+                        debug_variable!("pid rotation", omega);
+                        continue;
+                    }
+
                     let heading = read_mutex!(HEADING_MUTEX);
-                    let angle = clamp_angle(heading - target);
-                    let rotation = pid.next_control_output(angle).output;
+                    let rate = read_mutex!(HEADING_RATE_MUTEX);
+                    let rotation = pid.next(target, heading, rate);
                     ROTATION_SIGNAL.signal(rotation);
                     debug_variable!("pid rotation", rotation);
                 }
@@ -199,7 +546,10 @@ async fn drive_task() {
             Either::Second(data) => rotation = data,
         }
 
-        drive(speed, angle, rotation);
+        let (vx, vy, omega) = drive(speed, angle, rotation).await;
+        debug_variable!("commanded vx", vx);
+        debug_variable!("commanded vy", vy);
+        debug_variable!("commanded omega", omega);
     }
 }
 
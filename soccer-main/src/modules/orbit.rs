@@ -0,0 +1,78 @@
+use crate::{
+    modules::{COORDINATE_SIGNAL, HEADING_SIGNAL},
+    utils::{clamp_angle, construct_vector, debug::debug_variable},
+};
+use num_traits::Float;
+
+const STEP_DISTANCE: f32 = 12.;
+const STEP_ANGLE_MAX: f32 = 60.;
+const HYSTERESIS_ANGLE: f32 = 20.;
+
+// circles the ball to end up behind it for the approach angle, which is the field angle the ball
+// would be pushed towards, keeping the direction it started in when both ways are about as short
+#[derive(Default)]
+pub struct Orbit {
+    direction: f32,
+}
+
+impl Orbit {
+    // returns the next target on the circle and the angle left to travel around the ball
+    pub fn next(
+        &mut self,
+        ball: (f32, f32),
+        position: (f32, f32),
+        radius: f32,
+        approach: f32,
+    ) -> ((f32, f32), f32) {
+        let (bx, by) = ball;
+        let (x, y) = position;
+
+        let (_, bearing) = construct_vector(x - bx, by - y);
+        let bearing = bearing.to_degrees();
+        let remaining = clamp_angle(approach + 180. - bearing);
+
+        if remaining.abs() < 180. - HYSTERESIS_ANGLE || self.direction == 0. {
+            self.direction = remaining.signum();
+        }
+
+        let travel = if remaining.signum() == self.direction {
+            remaining.abs()
+        } else {
+            360. - remaining.abs()
+        };
+
+        let step = (STEP_DISTANCE / radius.max(f32::EPSILON))
+            .to_degrees()
+            .min(STEP_ANGLE_MAX)
+            .min(travel);
+        let (sin, cos) = (bearing + self.direction * step).to_radians().sin_cos();
+
+        ((bx + radius * sin, by - radius * cos), travel)
+    }
+
+    pub async fn run(
+        &mut self,
+        ball: (f32, f32),
+        position: (f32, f32),
+        radius: f32,
+        approach: f32,
+        face_ball: bool,
+    ) -> f32 {
+        let (target, travel) = self.next(ball, position, radius, approach);
+
+        COORDINATE_SIGNAL.signal(target);
+        if face_ball {
+            HEADING_SIGNAL.signal(facing(ball, position));
+        }
+
+        debug_variable!("orbit remaining", travel);
+
+        travel
+    }
+}
+
+// the heading that points the front of the robot at the ball
+pub fn facing(ball: (f32, f32), position: (f32, f32)) -> f32 {
+    let (_, angle) = construct_vector(ball.0 - position.0, position.1 - ball.1);
+    clamp_angle(angle.to_degrees())
+}
@@ -0,0 +1,140 @@
+use crate::constants::{FIELD_LENGTH, FIELD_MARGIN, FIELD_WIDTH, GOAL_DEPTH, GOAL_WIDTH};
+use nalgebra::Vector2;
+use num_traits::Float;
+
+const ATTRACTIVE_DISTANCE: f32 = 30.;
+const BALL_INFLUENCE: f32 = 25.;
+const BALL_STRENGTH: f32 = 1.5;
+const OBSTACLE_INFLUENCE: f32 = 35.;
+const OBSTACLE_STRENGTH: f32 = 2.;
+const ZONE_INFLUENCE: f32 = 15.;
+const ZONE_STRENGTH: f32 = 2.;
+const TANGENTIAL_RATIO: f32 = 1.;
+
+pub struct Zone {
+    pub x1: f32,
+    pub y1: f32,
+    pub x2: f32,
+    pub y2: f32,
+}
+
+impl Zone {
+    pub const fn new(x1: f32, y1: f32, x2: f32, y2: f32) -> Self {
+        Self { x1, y1, x2, y2 }
+    }
+
+    pub fn contains(&self, point: Vector2<f32>) -> bool {
+        (self.x1..=self.x2).contains(&point.x) && (self.y1..=self.y2).contains(&point.y)
+    }
+
+    pub fn closest(&self, point: Vector2<f32>) -> Vector2<f32> {
+        Vector2::new(
+            point.x.clamp(self.x1, self.x2),
+            point.y.clamp(self.y1, self.y2),
+        )
+    }
+}
+
+pub const FORBIDDEN_ZONES: [Zone; 2] = [
+    Zone::new(
+        (FIELD_WIDTH - GOAL_WIDTH) / 2.,
+        FIELD_MARGIN - GOAL_DEPTH,
+        (FIELD_WIDTH + GOAL_WIDTH) / 2.,
+        FIELD_MARGIN,
+    ),
+    Zone::new(
+        (FIELD_WIDTH - GOAL_WIDTH) / 2.,
+        FIELD_LENGTH - FIELD_MARGIN,
+        (FIELD_WIDTH + GOAL_WIDTH) / 2.,
+        FIELD_LENGTH - FIELD_MARGIN + GOAL_DEPTH,
+    ),
+];
+
+fn attractive(position: Vector2<f32>, target: Vector2<f32>) -> Vector2<f32> {
+    let diff = target - position;
+    let distance = diff.norm();
+
+    if distance < f32::EPSILON {
+        return Vector2::zeros();
+    }
+
+    diff / distance * (distance / ATTRACTIVE_DISTANCE).min(1.)
+}
+
+fn repulsive(
+    position: Vector2<f32>,
+    obstacle: Vector2<f32>,
+    target: Vector2<f32>,
+    influence: f32,
+    strength: f32,
+) -> Vector2<f32> {
+    let diff = position - obstacle;
+    let distance = diff.norm();
+
+    if distance >= influence || distance < f32::EPSILON {
+        return Vector2::zeros();
+    }
+
+    // the tangential component steers around the obstacle towards the side of the target, which
+    // keeps the robot from settling in the local minimum directly behind it
+    let away = diff / distance;
+    let side = away.perp(&(target - position)).signum();
+    let tangent = Vector2::new(-away.y, away.x) * side;
+
+    let magnitude = strength * (1. / distance - 1. / influence) * influence;
+
+    (away + tangent * TANGENTIAL_RATIO) * magnitude
+}
+
+pub fn plan(
+    position: (f32, f32),
+    target: (f32, f32),
+    ball: (f32, f32, bool),
+    obstacles: &[Option<(f32, f32)>],
+) -> (f32, f32) {
+    let position = Vector2::new(position.0, position.1);
+    let target = Vector2::new(target.0, target.1);
+
+    let mut force = attractive(position, target);
+
+    // only avoid the ball when the target is behind it and we are still in front of it
+    let (bx, by, bok) = ball;
+    if bok && target.y > by && position.y < by {
+        force += repulsive(
+            position,
+            Vector2::new(bx, by),
+            target,
+            BALL_INFLUENCE,
+            BALL_STRENGTH,
+        );
+    }
+
+    for (ox, oy) in obstacles.iter().flatten() {
+        force += repulsive(
+            position,
+            Vector2::new(*ox, *oy),
+            target,
+            OBSTACLE_INFLUENCE,
+            OBSTACLE_STRENGTH,
+        );
+    }
+
+    for zone in FORBIDDEN_ZONES.iter() {
+        if !zone.contains(target) {
+            force += repulsive(
+                position,
+                zone.closest(position),
+                target,
+                ZONE_INFLUENCE,
+                ZONE_STRENGTH,
+            );
+        }
+    }
+
+    if force.norm() < f32::EPSILON {
+        let diff = target - position;
+        return (diff.x, diff.y);
+    }
+
+    (force.x, force.y)
+}
@@ -0,0 +1,106 @@
+use crate::{
+    config::get_config,
+    constants::BALLCAP_DISTANCE,
+    modules::{Role, BALL_MUTEX, COORDINATE_MUTEX, ROLE_MUTEX, STUCK_MUTEX, TEAMMATE_MUTEX},
+    utils::{construct_vector, debug::debug_variable, read_mutex, write_mutex},
+};
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_time::Timer;
+use num_traits::Float;
+
+const ROLE_INTERVAL: u64 = 100;
+const ANGLE_WEIGHT: f32 = 0.3;
+const ROLE_HYSTERESIS: f32 = 20.;
+
+pub const LINK_TIMEOUT: u64 = 1000;
+pub const COST_MAX: f32 = 999.;
+
+// how far the robot has to go to get behind the ball, with a penalty for having to come around it
+pub async fn cost() -> f32 {
+    let (x, y, ok) = read_mutex!(COORDINATE_MUTEX);
+    let (bx, by, bok) = read_mutex!(BALL_MUTEX);
+
+    if !ok || !bok || read_mutex!(STUCK_MUTEX) {
+        return COST_MAX;
+    }
+
+    let (distance, angle) = construct_vector(x - bx, by + BALLCAP_DISTANCE - y);
+    let around = 180. - angle.to_degrees().abs();
+
+    distance + around * ANGLE_WEIGHT
+}
+
+#[embassy_executor::task]
+async fn role_task() {
+    let mut role = Role::Striker;
+
+    loop {
+        Timer::after_millis(ROLE_INTERVAL).await;
+
+        let fallback = if get_config!(goalie) {
+            Role::Goalie
+        } else {
+            Role::Striker
+        };
+
+        let teammate = read_mutex!(TEAMMATE_MUTEX).filter(|teammate| {
+            teammate.last_seen.elapsed().as_millis() < LINK_TIMEOUT
+                && teammate.started
+                && !teammate.stuck
+        });
+
+        let new_role = match teammate {
+            None => fallback,
+            Some(teammate) => {
+                let cost = cost().await;
+
+                if teammate.role == role {
+                    // both want the same role, so the better placed one attacks
+                    if cost < teammate.cost {
+                        Role::Striker
+                    } else if cost > teammate.cost {
+                        Role::Goalie
+                    } else {
+                        fallback
+                    }
+                } else if role == Role::Striker && cost > teammate.cost + ROLE_HYSTERESIS {
+                    Role::Goalie
+                } else if role == Role::Goalie && cost + ROLE_HYSTERESIS < teammate.cost {
+                    Role::Striker
+                } else {
+                    role
+                }
+            }
+        };
+
+        if new_role != role {
+            info!(
+                "Switching role to {} (teammate {})",
+                if new_role == Role::Goalie {
+                    "goalie"
+                } else {
+                    "striker"
+                },
+                teammate.map(|teammate| teammate.id)
+            );
+            role = new_role;
+        }
+
+        write_mutex!(ROLE_MUTEX, role);
+        debug_variable!(
+            "role",
+            if role == Role::Goalie {
+                "goalie"
+            } else {
+                "striker"
+            }
+        );
+    }
+}
+
+pub async fn init(spawner: &Spawner) {
+    info!("Starting role");
+
+    spawner.must_spawn(role_task());
+}
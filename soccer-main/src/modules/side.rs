@@ -0,0 +1,33 @@
+use crate::{
+    config::{get_config, set_config},
+    modules::trajectory,
+    utils::{clamp_angle, debug::debug_variable},
+};
+use defmt::info;
+
+// the field frame always has us attacking towards y = 0, so playing towards the other goal only
+// turns the zero heading around and the lidar sees the field from the other end
+pub fn mirror_heading(heading: f32) -> f32 {
+    clamp_angle(heading + 180.)
+}
+
+pub async fn mirrored() -> bool {
+    get_config!(mirrored)
+}
+
+pub async fn set(mirrored: bool) {
+    if get_config!(mirrored) == mirrored {
+        return;
+    }
+
+    info!("Switching sides (mirrored: {})", mirrored);
+    set_config!(mirrored, mirrored);
+    debug_variable!("mirrored", mirrored);
+
+    // waypoints are in field coordinates and would lead somewhere else after the switch
+    trajectory::cancel().await;
+}
+
+pub async fn switch() {
+    set(!get_config!(mirrored)).await;
+}
@@ -0,0 +1,120 @@
+use crate::{
+    config::get_config,
+    modules::{
+        movement::MOTION_MUTEX, Frame, Velocity, COORDINATE_MUTEX, HEADING_MUTEX, STUCK_MUTEX,
+        VELOCITY_SIGNAL,
+    },
+    utils::{clamp_angle, construct_vector, debug::debug_variable, read_mutex, write_mutex},
+};
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_time::{Instant, Timer};
+use num_traits::Float;
+
+const CHECK_INTERVAL: u64 = 100;
+const MOTION_MIN: f32 = 0.2;
+const PROGRESS_DISTANCE: f32 = 5.;
+const PROGRESS_ANGLE: f32 = 10.;
+
+const BACK_OFF_SPEED: f32 = 0.5;
+const BACK_OFF_DURATION: u64 = 400;
+const ROTATE_SPEED: f32 = 0.5;
+const ROTATE_DURATION: u64 = 300;
+const SIDESTEP_SPEED: f32 = 0.5;
+const SIDESTEP_DURATION: u64 = 400;
+
+async fn escape(angle: f32) {
+    let (sin, cos) = angle.to_radians().sin_cos();
+
+    let steps = [
+        (
+            Velocity {
+                x: -sin * BACK_OFF_SPEED,
+                y: -cos * BACK_OFF_SPEED,
+                omega: None,
+                frame: Frame::Robot,
+            },
+            BACK_OFF_DURATION,
+        ),
+        (
+            Velocity {
+                x: 0.,
+                y: 0.,
+                omega: Some(ROTATE_SPEED),
+                frame: Frame::Robot,
+            },
+            ROTATE_DURATION,
+        ),
+        (
+            Velocity {
+                x: -cos * SIDESTEP_SPEED,
+                y: sin * SIDESTEP_SPEED,
+                omega: None,
+                frame: Frame::Robot,
+            },
+            SIDESTEP_DURATION,
+        ),
+    ];
+
+    for (velocity, duration) in steps {
+        if !get_config!(started) {
+            break;
+        }
+        VELOCITY_SIGNAL.signal(velocity);
+        Timer::after_millis(duration).await;
+    }
+}
+
+#[embassy_executor::task]
+async fn stuck_task() {
+    let mut reference = (0., 0., 0.);
+    let mut last_progress = Instant::now();
+
+    loop {
+        Timer::after_millis(CHECK_INTERVAL).await;
+
+        let heading = read_mutex!(HEADING_MUTEX);
+        let (x, y, ok) = read_mutex!(COORDINATE_MUTEX);
+        let (speed, angle, _) = read_mutex!(MOTION_MUTEX);
+
+        let (moved, _) = construct_vector(x - reference.0, y - reference.1);
+        let turned = clamp_angle(heading - reference.2).abs();
+        let duration = get_config!(stuck_duration);
+
+        if !get_config!(started)
+            || !ok
+            || duration == 0
+            || speed.abs() < MOTION_MIN
+            || moved > PROGRESS_DISTANCE
+            || turned > PROGRESS_ANGLE
+        {
+            reference = (x, y, heading);
+            last_progress = Instant::now();
+            continue;
+        }
+
+        if last_progress.elapsed().as_millis() < duration {
+            continue;
+        }
+
+        info!("Stuck for {} ms, escaping", duration);
+        debug_variable!("stuck", true);
+        write_mutex!(STUCK_MUTEX, true);
+
+        escape(angle).await;
+
+        write_mutex!(STUCK_MUTEX, false);
+        debug_variable!("stuck", false);
+
+        let heading = read_mutex!(HEADING_MUTEX);
+        let (x, y, _) = read_mutex!(COORDINATE_MUTEX);
+        reference = (x, y, heading);
+        last_progress = Instant::now();
+    }
+}
+
+pub async fn init(spawner: &Spawner) {
+    info!("Starting stuck");
+
+    spawner.must_spawn(stuck_task());
+}
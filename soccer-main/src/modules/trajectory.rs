@@ -0,0 +1,220 @@
+use crate::{
+    config::get_config,
+    modules::{
+        Frame, Velocity, COORDINATE_MUTEX, COORDINATE_SIGNAL, HEADING_SIGNAL, TRAJECTORY_MUTEX,
+        VELOCITY_SIGNAL,
+    },
+    utils::{construct_vector, debug::debug_variable, read_mutex, write_mutex},
+};
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex, signal::Signal};
+use embassy_time::Timer;
+use heapless::Vec;
+use num_traits::Float;
+
+const MAX_WAYPOINTS: usize = 16;
+const CONTROL_INTERVAL: u64 = 20;
+const LOOKAHEAD_DISTANCE: f32 = 15.;
+const SLOWDOWN_DISTANCE: f32 = 20.;
+const FINISH_DISTANCE: f32 = 3.;
+const MIN_SPEED: f32 = 0.1;
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Waypoint {
+    pub x: f32,
+    pub y: f32,
+    pub heading: f32,
+    pub speed: f32,
+}
+
+static WAYPOINTS: Mutex<CriticalSectionRawMutex, Vec<Waypoint, MAX_WAYPOINTS>> =
+    Mutex::new(Vec::new());
+
+static RUN_SIGNAL: Signal<CriticalSectionRawMutex, bool> = Signal::new();
+
+pub async fn add_waypoint(waypoint: Waypoint) -> bool {
+    let mut waypoints = WAYPOINTS.lock().await;
+    waypoints.push(waypoint).is_ok()
+}
+
+pub async fn clear() {
+    RUN_SIGNAL.signal(false);
+    let mut waypoints = WAYPOINTS.lock().await;
+    waypoints.clear();
+}
+
+pub async fn start() {
+    RUN_SIGNAL.signal(true);
+}
+
+pub async fn cancel() {
+    RUN_SIGNAL.signal(false);
+}
+
+// walks along the route from the projection on the current segment to find the lookahead point
+fn lookahead(
+    position: (f32, f32),
+    start: (f32, f32),
+    route: &[Waypoint],
+    index: usize,
+) -> ((f32, f32), f32, bool) {
+    let (px, py) = position;
+    let (sx, sy) = start;
+    let (ex, ey) = (route[index].x, route[index].y);
+
+    let (length, _) = construct_vector(ex - sx, ey - sy);
+    let t = if length > f32::EPSILON {
+        (((px - sx) * (ex - sx) + (py - sy) * (ey - sy)) / (length * length)).clamp(0., 1.)
+    } else {
+        1.
+    };
+
+    let mut remaining = LOOKAHEAD_DISTANCE;
+    let mut current = (sx + (ex - sx) * t, sy + (ey - sy) * t);
+    let travelled = length * t;
+    let passed = t >= 1.;
+
+    for waypoint in &route[index..] {
+        let (distance, _) = construct_vector(waypoint.x - current.0, waypoint.y - current.1);
+        if distance >= remaining {
+            let ratio = remaining / distance;
+            current = (
+                current.0 + (waypoint.x - current.0) * ratio,
+                current.1 + (waypoint.y - current.1) * ratio,
+            );
+            return (current, travelled, passed);
+        }
+        remaining -= distance;
+        current = (waypoint.x, waypoint.y);
+    }
+
+    (current, travelled, passed)
+}
+
+async fn run(route: &[Waypoint]) -> bool {
+    let (x, y, _) = read_mutex!(COORDINATE_MUTEX);
+
+    let mut start = (x, y);
+    let mut index = 0;
+    let mut completed = 0.;
+
+    let total = route
+        .iter()
+        .fold((0., start), |(total, last), waypoint| {
+            let (distance, _) = construct_vector(waypoint.x - last.0, waypoint.y - last.1);
+            (total + distance, (waypoint.x, waypoint.y))
+        })
+        .0;
+
+    HEADING_SIGNAL.signal(route[0].heading);
+
+    loop {
+        match select(Timer::after_millis(CONTROL_INTERVAL), RUN_SIGNAL.wait()).await {
+            Either::First(_) => {}
+            Either::Second(true) => {}
+            Either::Second(false) => return false,
+        }
+
+        if !get_config!(started) {
+            return false;
+        }
+
+        let (x, y, ok) = read_mutex!(COORDINATE_MUTEX);
+        if !ok {
+            VELOCITY_SIGNAL.signal(Velocity {
+                x: 0.,
+                y: 0.,
+                omega: None,
+                frame: Frame::Field,
+            });
+            continue;
+        }
+
+        let last = index + 1 == route.len();
+        let waypoint = route[index];
+        let (target, travelled, passed) = lookahead((x, y), start, route, index);
+
+        let (distance, _) = construct_vector(waypoint.x - x, waypoint.y - y);
+        if last && distance < FINISH_DISTANCE {
+            break;
+        }
+
+        if !last && (passed || distance < LOOKAHEAD_DISTANCE) {
+            let (length, _) = construct_vector(waypoint.x - start.0, waypoint.y - start.1);
+            completed += length;
+            start = (waypoint.x, waypoint.y);
+            index += 1;
+            HEADING_SIGNAL.signal(route[index].heading);
+            continue;
+        }
+
+        let fraction = if total > f32::EPSILON {
+            ((completed + travelled) / total).min(1.)
+        } else {
+            1.
+        };
+        debug_variable!("trajectory waypoint", index);
+        debug_variable!("trajectory progress", fraction);
+
+        let mut speed = waypoint.speed.abs().min(1.);
+        if last {
+            speed *= (distance / SLOWDOWN_DISTANCE).clamp(MIN_SPEED, 1.);
+        }
+
+        let (dx, dy) = (target.0 - x, target.1 - y);
+        let (magnitude, _) = construct_vector(dx, dy);
+        let magnitude = magnitude.max(f32::EPSILON);
+
+        VELOCITY_SIGNAL.signal(Velocity {
+            x: dx / magnitude * speed,
+            y: dy / magnitude * speed,
+            omega: None,
+            frame: Frame::Field,
+        });
+    }
+
+    // hold the final pose with the position controller
+    let waypoint = route[route.len() - 1];
+    COORDINATE_SIGNAL.signal((waypoint.x, waypoint.y));
+    HEADING_SIGNAL.signal(waypoint.heading);
+
+    debug_variable!("trajectory progress", 1.);
+
+    true
+}
+
+#[embassy_executor::task]
+async fn trajectory_task() {
+    loop {
+        if !RUN_SIGNAL.wait().await {
+            continue;
+        }
+
+        let route = WAYPOINTS.lock().await.clone();
+        if route.is_empty() {
+            continue;
+        }
+
+        info!("Following trajectory of {} waypoints", route.len());
+        write_mutex!(TRAJECTORY_MUTEX, true);
+        debug_variable!("trajectory progress", 0.);
+        debug_variable!("trajectory", true);
+
+        let finished = run(&route).await;
+        info!(
+            "Trajectory {}",
+            if finished { "finished" } else { "cancelled" }
+        );
+
+        write_mutex!(TRAJECTORY_MUTEX, false);
+        debug_variable!("trajectory", false);
+    }
+}
+
+pub async fn init(spawner: &Spawner) {
+    info!("Starting trajectory");
+
+    spawner.must_spawn(trajectory_task());
+}
@@ -0,0 +1,207 @@
+use crate::{
+    config::set_config,
+    constants::{FIELD_LENGTH, FIELD_WIDTH},
+    modules::{movement::drive, COORDINATE_MUTEX, HEADING_CHANGED, HEADING_MUTEX},
+    utils::{clamp_angle, construct_vector, debug::debug_variable, read_mutex, stop},
+};
+use core::f32::consts::PI;
+use defmt::{info, warn};
+use embassy_time::{Instant, Timer};
+use heapless::Vec;
+use num_traits::Float;
+
+const RELAY_OUTPUT: f32 = 0.3;
+const RELAY_HYSTERESIS: f32 = 2.;
+const RELAY_CYCLES: u32 = 6;
+const RELAY_SKIP_CYCLES: u32 = 2;
+const RELAY_TIMEOUT: u64 = 10000;
+
+const STEP_SPEED: f32 = 0.4;
+const STEP_DISTANCE: f32 = 60.;
+const STEP_DURATION: u64 = 1200;
+const STEP_SAMPLES: usize = 128;
+const SAMPLE_DURATION: u64 = 10;
+
+pub async fn tune_heading(apply: bool) {
+    stop().await;
+
+    let Ok(mut subscriber) = HEADING_CHANGED.subscriber() else {
+        warn!("Error subscribing to heading");
+        return;
+    };
+
+    let target = read_mutex!(HEADING_MUTEX);
+    let start = Instant::now();
+
+    let mut output = RELAY_OUTPUT;
+    let mut last_switch: Option<Instant> = None;
+    let mut high = f32::MIN;
+    let mut low = f32::MAX;
+
+    let mut cycles = 0;
+    let mut updates = 0;
+    let mut counted = 0;
+    let mut period_total = 0.;
+    let mut amplitude_total = 0.;
+
+    drive(0., 0., output).await;
+
+    while cycles < RELAY_CYCLES && start.elapsed().as_millis() < RELAY_TIMEOUT {
+        subscriber.next_message().await;
+        updates += 1;
+
+        let error = clamp_angle(read_mutex!(HEADING_MUTEX) - target);
+        high = high.max(error);
+        low = low.min(error);
+
+        let new_output = if error > RELAY_HYSTERESIS {
+            -RELAY_OUTPUT
+        } else if error < -RELAY_HYSTERESIS {
+            RELAY_OUTPUT
+        } else {
+            output
+        };
+
+        if new_output == output {
+            continue;
+        }
+
+        output = new_output;
+        drive(0., 0., output).await;
+
+        // a full cycle ends every time the relay switches back to a positive output
+        if output > 0. {
+            if let Some(last) = last_switch {
+                cycles += 1;
+                if cycles > RELAY_SKIP_CYCLES {
+                    period_total += last.elapsed().as_micros() as f32 / 1000000.;
+                    amplitude_total += (high - low) / 2.;
+                    counted += 1;
+                }
+            }
+            last_switch = Some(Instant::now());
+            high = f32::MIN;
+            low = f32::MAX;
+        }
+    }
+
+    drive(0., 0., 0.).await;
+
+    if counted == 0 {
+        warn!("Heading did not oscillate during tuning");
+        return;
+    }
+
+    let period = period_total / counted as f32;
+    let amplitude = amplitude_total / counted as f32;
+    let interval = start.elapsed().as_micros() as f32 / 1000000. / updates as f32;
+
+    let amplitude_relay = (amplitude * amplitude - RELAY_HYSTERESIS * RELAY_HYSTERESIS)
+        .max(f32::EPSILON)
+        .sqrt();
+    let ultimate_gain = 4. * RELAY_OUTPUT / (PI * amplitude_relay);
+
+    // ziegler-nichols rules for a pid controller, with i and d scaled to heading updates
+    let p = 0.6 * ultimate_gain;
+    let i = p * interval / (period / 2.);
+    let d = p * period / 8. / interval;
+
+    info!(
+        "Heading tuning period: {}, amplitude: {}, p: {}, i: {}, d: {}",
+        period, amplitude, p, i, d
+    );
+
+    debug_variable!("tune heading period", period);
+    debug_variable!("tune heading amplitude", amplitude);
+    debug_variable!("tune heading p", p);
+    debug_variable!("tune heading i", i);
+    debug_variable!("tune heading d", d);
+
+    if apply {
+        set_config!(pid_p, p);
+        set_config!(pid_i, i);
+        set_config!(pid_d, d);
+    }
+}
+
+pub async fn tune_position(apply: bool) {
+    stop().await;
+
+    let heading = read_mutex!(HEADING_MUTEX);
+    let (x, y, ok) = read_mutex!(COORDINATE_MUTEX);
+
+    if !ok {
+        warn!("Position is unknown for tuning");
+        return;
+    }
+
+    let (_, angle) = construct_vector(FIELD_WIDTH / 2. - x, y - FIELD_LENGTH / 2.);
+    let (sin, cos) = angle.sin_cos();
+    let angle = clamp_angle(angle.to_degrees() - heading);
+
+    let start = Instant::now();
+    let mut samples: Vec<(f32, f32), STEP_SAMPLES> = Vec::new();
+    let mut last = (x, y);
+
+    drive(STEP_SPEED, angle, 0.).await;
+
+    while start.elapsed().as_millis() < STEP_DURATION {
+        Timer::after_millis(SAMPLE_DURATION).await;
+
+        let (new_x, new_y, new_ok) = read_mutex!(COORDINATE_MUTEX);
+        if !new_ok {
+            break;
+        }
+        if (new_x, new_y) == last {
+            continue;
+        }
+        last = (new_x, new_y);
+
+        let time = start.elapsed().as_micros() as f32 / 1000000.;
+        let travelled = (new_x - x) * sin - (new_y - y) * cos;
+
+        if samples.push((time, travelled)).is_err() || travelled > STEP_DISTANCE {
+            break;
+        }
+    }
+
+    drive(0., 0., 0.).await;
+
+    if samples.len() < 4 {
+        warn!("Not enough position samples for tuning");
+        return;
+    }
+
+    // the position loop integrates speed, so fit the final slope and its intercept as dead time
+    let (time_mid, travelled_mid) = samples[samples.len() / 2];
+    let (time_end, travelled_end) = samples[samples.len() - 1];
+    let velocity = (travelled_end - travelled_mid) / (time_end - time_mid);
+
+    if velocity <= f32::EPSILON {
+        warn!("Robot did not move during tuning");
+        return;
+    }
+
+    let interval = time_end / samples.len() as f32;
+    let delay = (time_end - travelled_end / velocity).max(interval);
+    let gain = velocity / STEP_SPEED;
+
+    // simc rules for an integrating process with the closed loop time constant set to the delay
+    let p = 1. / (2. * gain * delay);
+    let d = p * delay / 2. / interval;
+
+    info!(
+        "Position tuning velocity: {}, delay: {}, p: {}, d: {}",
+        velocity, delay, p, d
+    );
+
+    debug_variable!("tune position velocity", velocity);
+    debug_variable!("tune position delay", delay);
+    debug_variable!("tune position p", p);
+    debug_variable!("tune position d", d);
+
+    if apply {
+        set_config!(pid2_p, p);
+        set_config!(pid2_d, d);
+    }
+}
@@ -0,0 +1,288 @@
+use crate::utils::clamp_angle;
+use num_traits::Float;
+
+pub const MAX_CODE: usize = 512;
+pub const REGISTERS: usize = 8;
+
+const STACK_SIZE: usize = 16;
+const MAX_STEPS: usize = 512;
+
+// every value is an f32, with zero as false and anything else as true, and angles in degrees
+pub mod op {
+    pub const HALT: u8 = 0x00;
+    // followed by a little endian f32
+    pub const PUSH: u8 = 0x01;
+    // followed by the input index
+    pub const LOAD: u8 = 0x02;
+    // followed by the register index, registers keep their values from one run to the next
+    pub const GET: u8 = 0x03;
+    pub const SET: u8 = 0x04;
+    pub const DUP: u8 = 0x05;
+    pub const DROP: u8 = 0x06;
+    pub const SWAP: u8 = 0x07;
+
+    pub const ADD: u8 = 0x10;
+    pub const SUB: u8 = 0x11;
+    pub const MUL: u8 = 0x12;
+    pub const DIV: u8 = 0x13;
+    pub const NEG: u8 = 0x14;
+    pub const ABS: u8 = 0x15;
+    pub const MIN: u8 = 0x16;
+    pub const MAX: u8 = 0x17;
+    pub const SQRT: u8 = 0x18;
+    pub const SIN: u8 = 0x19;
+    pub const COS: u8 = 0x1a;
+    // atan2 of the first value pushed over the second, like the angles from construct_vector
+    pub const ATAN2: u8 = 0x1b;
+
+    pub const LT: u8 = 0x20;
+    pub const GT: u8 = 0x21;
+    pub const EQ: u8 = 0x22;
+    pub const NOT: u8 = 0x23;
+    pub const AND: u8 = 0x24;
+    pub const OR: u8 = 0x25;
+
+    // followed by a little endian u16 offset into the code
+    pub const JMP: u8 = 0x30;
+    pub const JZ: u8 = 0x31;
+
+    // pops y then x and sets the coordinate target
+    pub const MOVE: u8 = 0x40;
+    // pops the heading target
+    pub const HEAD: u8 = 0x41;
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Fault {
+    BadOpcode,
+    BadOperand,
+    BadJump,
+    StackOverflow,
+    StackUnderflow,
+    StepLimit,
+    NotFinite,
+    TooLong,
+}
+
+impl Fault {
+    pub fn name(self) -> &'static str {
+        match self {
+            Fault::BadOpcode => "bad opcode",
+            Fault::BadOperand => "bad operand",
+            Fault::BadJump => "bad jump",
+            Fault::StackOverflow => "stack overflow",
+            Fault::StackUnderflow => "stack underflow",
+            Fault::StepLimit => "step limit",
+            Fault::NotFinite => "not finite",
+            Fault::TooLong => "too long",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Output {
+    pub target: Option<(f32, f32)>,
+    pub heading: Option<f32>,
+}
+
+fn operand_len(opcode: u8) -> Option<usize> {
+    match opcode {
+        op::PUSH => Some(4),
+        op::LOAD | op::GET | op::SET => Some(1),
+        op::JMP | op::JZ => Some(2),
+        op::HALT
+        | op::DUP
+        | op::DROP
+        | op::SWAP
+        | op::ADD
+        | op::SUB
+        | op::MUL
+        | op::DIV
+        | op::NEG
+        | op::ABS
+        | op::MIN
+        | op::MAX
+        | op::SQRT
+        | op::SIN
+        | op::COS
+        | op::ATAN2
+        | op::LT
+        | op::GT
+        | op::EQ
+        | op::NOT
+        | op::AND
+        | op::OR
+        | op::MOVE
+        | op::HEAD => Some(0),
+        _ => None,
+    }
+}
+
+// checks that every instruction is complete, registers exist and jumps land on an instruction,
+// so that a script is rejected when it is uploaded rather than when it first runs
+pub fn validate(code: &[u8], inputs: usize) -> Result<(), Fault> {
+    if code.len() > MAX_CODE {
+        return Err(Fault::TooLong);
+    }
+
+    let mut starts = [false; MAX_CODE];
+    let mut pc = 0;
+    while pc < code.len() {
+        starts[pc] = true;
+        let len = operand_len(code[pc]).ok_or(Fault::BadOpcode)?;
+        let operand = code.get(pc + 1..pc + 1 + len).ok_or(Fault::BadOperand)?;
+
+        match code[pc] {
+            op::LOAD if operand[0] as usize >= inputs => return Err(Fault::BadOperand),
+            op::GET | op::SET if operand[0] as usize >= REGISTERS => return Err(Fault::BadOperand),
+            _ => {}
+        }
+
+        pc += 1 + len;
+    }
+
+    pc = 0;
+    while pc < code.len() {
+        let len = operand_len(code[pc]).unwrap_or(0);
+        if matches!(code[pc], op::JMP | op::JZ) {
+            let target = u16::from_le_bytes([code[pc + 1], code[pc + 2]]) as usize;
+            if target >= code.len() || !starts[target] {
+                return Err(Fault::BadJump);
+            }
+        }
+        pc += 1 + len;
+    }
+
+    Ok(())
+}
+
+struct Stack {
+    values: [f32; STACK_SIZE],
+    len: usize,
+}
+
+impl Stack {
+    fn push(&mut self, value: f32) -> Result<(), Fault> {
+        let slot = self.values.get_mut(self.len).ok_or(Fault::StackOverflow)?;
+        *slot = value;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<f32, Fault> {
+        self.len = self.len.checked_sub(1).ok_or(Fault::StackUnderflow)?;
+        Ok(self.values[self.len])
+    }
+
+    fn unary(&mut self, f: impl Fn(f32) -> f32) -> Result<(), Fault> {
+        let a = self.pop()?;
+        self.push(f(a))
+    }
+
+    // the first value pushed is the left hand side
+    fn binary(&mut self, f: impl Fn(f32, f32) -> f32) -> Result<(), Fault> {
+        let b = self.pop()?;
+        let a = self.pop()?;
+        self.push(f(a, b))
+    }
+}
+
+fn truth(value: bool) -> f32 {
+    if value {
+        1.
+    } else {
+        0.
+    }
+}
+
+fn finite(value: f32) -> Result<f32, Fault> {
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(Fault::NotFinite)
+    }
+}
+
+// runs the script from the start until it halts or falls off the end, within a fixed number of
+// steps so a script that loops forever cannot stall the strategy
+pub fn run(code: &[u8], inputs: &[f32], registers: &mut [f32; REGISTERS]) -> Result<Output, Fault> {
+    let mut stack = Stack {
+        values: [0.; STACK_SIZE],
+        len: 0,
+    };
+    let mut output = Output::default();
+    let mut pc = 0;
+
+    for _ in 0..MAX_STEPS {
+        let Some(&opcode) = code.get(pc) else {
+            return Ok(output);
+        };
+        let len = operand_len(opcode).ok_or(Fault::BadOpcode)?;
+        let operand = code.get(pc + 1..pc + 1 + len).ok_or(Fault::BadOperand)?;
+        pc += 1 + len;
+
+        match opcode {
+            op::HALT => return Ok(output),
+            op::PUSH => stack.push(f32::from_le_bytes(operand.try_into().unwrap()))?,
+            op::LOAD => stack.push(*inputs.get(operand[0] as usize).ok_or(Fault::BadOperand)?)?,
+            op::GET => stack.push(
+                *registers
+                    .get(operand[0] as usize)
+                    .ok_or(Fault::BadOperand)?,
+            )?,
+            op::SET => {
+                let value = finite(stack.pop()?)?;
+                *registers
+                    .get_mut(operand[0] as usize)
+                    .ok_or(Fault::BadOperand)? = value;
+            }
+            op::DUP => {
+                let a = stack.pop()?;
+                stack.push(a)?;
+                stack.push(a)?;
+            }
+            op::DROP => {
+                stack.pop()?;
+            }
+            op::SWAP => {
+                let b = stack.pop()?;
+                let a = stack.pop()?;
+                stack.push(b)?;
+                stack.push(a)?;
+            }
+            op::ADD => stack.binary(|a, b| a + b)?,
+            op::SUB => stack.binary(|a, b| a - b)?,
+            op::MUL => stack.binary(|a, b| a * b)?,
+            op::DIV => stack.binary(|a, b| a / b)?,
+            op::NEG => stack.unary(|a| -a)?,
+            op::ABS => stack.unary(|a| a.abs())?,
+            op::MIN => stack.binary(|a, b| a.min(b))?,
+            op::MAX => stack.binary(|a, b| a.max(b))?,
+            op::SQRT => stack.unary(|a| a.sqrt())?,
+            op::SIN => stack.unary(|a| a.to_radians().sin())?,
+            op::COS => stack.unary(|a| a.to_radians().cos())?,
+            op::ATAN2 => stack.binary(|a, b| a.atan2(b).to_degrees())?,
+            op::LT => stack.binary(|a, b| truth(a < b))?,
+            op::GT => stack.binary(|a, b| truth(a > b))?,
+            op::EQ => stack.binary(|a, b| truth(a == b))?,
+            op::NOT => stack.unary(|a| truth(a == 0.))?,
+            op::AND => stack.binary(|a, b| truth(a != 0. && b != 0.))?,
+            op::OR => stack.binary(|a, b| truth(a != 0. || b != 0.))?,
+            op::JMP | op::JZ => {
+                let target = u16::from_le_bytes([operand[0], operand[1]]) as usize;
+                if opcode == op::JMP || stack.pop()? == 0. {
+                    pc = target;
+                }
+            }
+            op::MOVE => {
+                let y = finite(stack.pop()?)?;
+                let x = finite(stack.pop()?)?;
+                output.target = Some((x, y));
+            }
+            op::HEAD => output.heading = Some(clamp_angle(finite(stack.pop()?)?)),
+            _ => return Err(Fault::BadOpcode),
+        }
+    }
+
+    Err(Fault::StepLimit)
+}
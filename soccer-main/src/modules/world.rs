@@ -0,0 +1,80 @@
+use crate::{
+    modules::{role::LINK_TIMEOUT, BALL_MUTEX, COORDINATE_MUTEX, TEAMMATE_MUTEX},
+    utils::{construct_vector, debug::debug_variable, read_mutex},
+};
+
+const TEAMMATE_BALL_TIMEOUT: u64 = 500;
+const CONFIDENCE_DISTANCE: f32 = 100.;
+const CONFIDENCE_MIN: f32 = 0.2;
+const AGREE_DISTANCE: f32 = 20.;
+
+// how much to trust our own sighting, which gets worse the further away the ball is
+pub async fn confidence() -> f32 {
+    let (x, y, ok) = read_mutex!(COORDINATE_MUTEX);
+    let (bx, by, bok) = read_mutex!(BALL_MUTEX);
+
+    if !ok || !bok {
+        return 0.;
+    }
+
+    let (distance, _) = construct_vector(bx - x, by - y);
+    1. / (1. + distance / CONFIDENCE_DISTANCE)
+}
+
+// the teammate's sighting with its confidence reduced by how old it is
+async fn teammate_ball() -> Option<(f32, f32, f32)> {
+    let teammate = read_mutex!(TEAMMATE_MUTEX)?;
+
+    if teammate.last_seen.elapsed().as_millis() > LINK_TIMEOUT || !teammate.ball.2 {
+        return None;
+    }
+
+    let age = teammate.ball_seen.elapsed().as_millis();
+    if age > TEAMMATE_BALL_TIMEOUT {
+        return None;
+    }
+
+    let confidence = teammate.ball_confidence * (1. - age as f32 / TEAMMATE_BALL_TIMEOUT as f32);
+    if confidence < CONFIDENCE_MIN {
+        return None;
+    }
+
+    Some((teammate.ball.0, teammate.ball.1, confidence))
+}
+
+// our ball estimate merged with the teammate's, falling back to theirs when we cannot see it
+pub async fn ball() -> (f32, f32, bool) {
+    let (bx, by, bok) = read_mutex!(BALL_MUTEX);
+    let teammate = teammate_ball().await;
+
+    match (bok, teammate) {
+        (true, Some((tx, ty, teammate_confidence))) => {
+            let (distance, _) = construct_vector(tx - bx, ty - by);
+            if distance < AGREE_DISTANCE {
+                let own_confidence = confidence().await;
+                let total = own_confidence + teammate_confidence;
+                debug_variable!("ball source", "merged");
+                (
+                    (bx * own_confidence + tx * teammate_confidence) / total,
+                    (by * own_confidence + ty * teammate_confidence) / total,
+                    true,
+                )
+            } else {
+                debug_variable!("ball source", "own");
+                (bx, by, true)
+            }
+        }
+        (true, None) => {
+            debug_variable!("ball source", "own");
+            (bx, by, true)
+        }
+        (false, Some((tx, ty, _))) => {
+            debug_variable!("ball source", "teammate");
+            (tx, ty, true)
+        }
+        (false, None) => {
+            debug_variable!("ball source", "none");
+            (bx, by, false)
+        }
+    }
+}
@@ -11,9 +11,11 @@ use embassy_rp::{
 };
 
 mod server;
+mod team;
 mod wireless;
 
 const SERVER_THREADS: usize = 4;
+const SOCKETS: usize = SERVER_THREADS + 1;
 const WIFI_NETWORK: &str = "RI-WLAN";
 const WIFI_PASSWORD: &str = "automatica";
 
@@ -53,5 +55,6 @@ pub async fn init(spawner: &Spawner, p: PeripheralsNetwork) {
     if let Ok((mut control, stack)) = wireless::init(spawner, pwr, spi, fw, clm).await {
         control.gpio_set(0, true).await;
         server::init(spawner, stack).await;
+        team::init(spawner, stack).await;
     }
 }
@@ -1,12 +1,19 @@
 use crate::{
     bootloader::{Command, BOOTLOADER_CHANNEL},
     built_info,
+    modules::{
+        game::{self, Event, GAME_MUTEX},
+        side,
+        vm::MAX_CODE,
+    },
     network::SERVER_THREADS,
+    params::{get_params, set_param},
+    strategy::script,
     utils::{
         debug::{get_functions, get_variables},
         functions::call_function,
         logger::LOGGER_CHANNEL,
-        stop,
+        read_mutex, stop,
     },
 };
 use cyw43::NetDriver;
@@ -15,7 +22,7 @@ use embassy_executor::Spawner;
 use embassy_futures::select::{select, Either};
 use embassy_net::Stack;
 use embassy_time::Duration;
-use heapless::Vec;
+use heapless::{String, Vec};
 use picoserve::{
     extract::Form,
     io::{Read, Write},
@@ -27,7 +34,7 @@ use picoserve::{
     routing::{get, post, PathRouter},
     Config as ServeConfig, Router, Timeouts,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use static_cell::make_static;
 
 type AppRouter = impl PathRouter;
@@ -126,6 +133,55 @@ struct Info {
     time: &'static str,
 }
 
+#[derive(Serialize)]
+struct GameInfo {
+    phase: &'static str,
+    clock: u64,
+    half: u8,
+}
+
+#[derive(Serialize)]
+struct SideInfo {
+    mirrored: bool,
+}
+
+#[derive(Serialize)]
+struct ScriptInfo {
+    length: usize,
+    active: bool,
+    fault: Option<&'static str>,
+}
+
+#[derive(Deserialize)]
+struct GameEvent {
+    event: String<32>,
+}
+
+#[derive(Deserialize)]
+struct ParamUpdate {
+    name: String<32>,
+    value: String<16>,
+}
+
+// scripts are uploaded as hex, two characters for each byte of code
+#[derive(Deserialize)]
+struct ScriptUpload {
+    code: String<{ MAX_CODE * 2 }>,
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8, MAX_CODE>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+
+    let mut code = Vec::new();
+    for i in (0..hex.len()).step_by(2) {
+        let byte = u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()?;
+        code.push(byte).ok()?;
+    }
+    Some(code)
+}
+
 fn make_router() -> Router<AppRouter> {
     Router::new()
         .route(
@@ -167,6 +223,79 @@ fn make_router() -> Router<AppRouter> {
             "/api/functions",
             get(|| async move { Json(get_functions().await).into_response() }),
         )
+        .route(
+            "/api/game",
+            get(|| async move {
+                let game = read_mutex!(GAME_MUTEX);
+                Json(GameInfo {
+                    phase: game.phase.name(),
+                    clock: game.clock,
+                    half: game.half,
+                })
+                .into_response()
+            })
+            .post(|Form(GameEvent { event })| async move {
+                match Event::from_name(&event) {
+                    Some(event) => {
+                        game::send(event).await;
+                        "Ok"
+                    }
+                    None => "Unknown event",
+                }
+            }),
+        )
+        .route(
+            "/api/side",
+            get(|| async move {
+                Json(SideInfo {
+                    mirrored: side::mirrored().await,
+                })
+                .into_response()
+            })
+            .post(|| async move {
+                side::switch().await;
+                "Ok"
+            }),
+        )
+        .route(
+            "/api/params",
+            get(|| async move { Json(get_params().await).into_response() }).post(
+                |Form(ParamUpdate { name, value })| async move {
+                    if set_param(&name, &value).await {
+                        "Ok"
+                    } else {
+                        "Invalid parameter"
+                    }
+                },
+            ),
+        )
+        .route(
+            "/api/script",
+            get(|| async move {
+                let (length, fault) = script::status().await;
+                Json(ScriptInfo {
+                    length,
+                    active: script::active().await,
+                    fault: fault.map(|fault| fault.name()),
+                })
+                .into_response()
+            })
+            .post(|Form(ScriptUpload { code })| async move {
+                // an empty upload unloads the script and hands back to the built-in strategies
+                if code.is_empty() {
+                    script::unload().await;
+                    return "Ok";
+                }
+
+                match decode_hex(&code) {
+                    Some(code) => match script::load(&code).await {
+                        Ok(()) => "Ok",
+                        Err(fault) => fault.name(),
+                    },
+                    None => "Invalid hex",
+                }
+            }),
+        )
         .route(
             "/api/execute",
             post(|Form(func)| async move {
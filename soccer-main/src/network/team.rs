@@ -0,0 +1,260 @@
+use crate::{
+    config::get_config,
+    modules::{
+        game::{Phase, GAME_MUTEX},
+        role,
+        role::LINK_TIMEOUT,
+        side, world, Role, Teammate, BALL_MUTEX, BALL_SEEN_MUTEX, COORDINATE_MUTEX, HEADING_MUTEX,
+        ROLE_MUTEX, STUCK_MUTEX, TEAMMATE_MUTEX,
+    },
+    utils::{debug::debug_variable, read_mutex, write_mutex},
+};
+use cyw43::NetDriver;
+use defmt::{info, warn};
+use embassy_executor::Spawner;
+use embassy_futures::select::{select, Either};
+use embassy_net::{
+    udp::{PacketMetadata, UdpSocket},
+    IpEndpoint, Ipv4Address, Stack,
+};
+use embassy_time::{Duration, Instant, Timer};
+
+const TEAM_PORT: u16 = 4210;
+const TEAM_INTERVAL: u64 = 100;
+
+const PACKET_MAGIC: u8 = 0x5a;
+const PACKET_LEN: usize = 32;
+
+const FLAG_POSITION: u8 = 1 << 0;
+const FLAG_BALL: u8 = 1 << 1;
+const FLAG_STARTED: u8 = 1 << 2;
+const FLAG_STUCK: u8 = 1 << 3;
+const FLAG_MIRRORED: u8 = 1 << 4;
+const FLAG_KICKED_OFF: u8 = 1 << 5;
+
+fn read_f32(packet: &[u8], offset: usize) -> f32 {
+    f32::from_le_bytes(packet[offset..offset + 4].try_into().unwrap())
+}
+
+// magic, id, role, flags, then x, y, heading, ball x, ball y and cost as little endian floats,
+// followed by the ball age in milliseconds, its confidence out of 255 and the game phase
+fn encode(teammate: &Teammate) -> [u8; PACKET_LEN] {
+    let mut packet = [0; PACKET_LEN];
+
+    let mut flags = 0;
+    if teammate.position.2 {
+        flags |= FLAG_POSITION;
+    }
+    if teammate.ball.2 {
+        flags |= FLAG_BALL;
+    }
+    if teammate.started {
+        flags |= FLAG_STARTED;
+    }
+    if teammate.stuck {
+        flags |= FLAG_STUCK;
+    }
+    if teammate.mirrored {
+        flags |= FLAG_MIRRORED;
+    }
+    if teammate.kicked_off {
+        flags |= FLAG_KICKED_OFF;
+    }
+
+    packet[0] = PACKET_MAGIC;
+    packet[1] = teammate.id;
+    packet[2] = (teammate.role == Role::Goalie) as u8;
+    packet[3] = flags;
+
+    let values = [
+        teammate.position.0,
+        teammate.position.1,
+        teammate.heading,
+        teammate.ball.0,
+        teammate.ball.1,
+        teammate.cost,
+    ];
+    for (index, value) in values.iter().enumerate() {
+        packet[4 + index * 4..8 + index * 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    let age = teammate
+        .ball_seen
+        .elapsed()
+        .as_millis()
+        .min(u16::MAX as u64) as u16;
+    packet[28..30].copy_from_slice(&age.to_le_bytes());
+    packet[30] = (teammate.ball_confidence.clamp(0., 1.) * 255.) as u8;
+    packet[31] = teammate.phase.code();
+
+    packet
+}
+
+fn decode(packet: &[u8]) -> Option<Teammate> {
+    if packet.len() != PACKET_LEN || packet[0] != PACKET_MAGIC {
+        return None;
+    }
+
+    let flags = packet[3];
+    let phase = Phase::from_code(packet[31])?;
+    let age = u16::from_le_bytes([packet[28], packet[29]]) as u64;
+    let now = Instant::now();
+
+    Some(Teammate {
+        id: packet[1],
+        role: if packet[2] == 1 {
+            Role::Goalie
+        } else {
+            Role::Striker
+        },
+        position: (
+            read_f32(packet, 4),
+            read_f32(packet, 8),
+            flags & FLAG_POSITION != 0,
+        ),
+        heading: read_f32(packet, 12),
+        ball: (
+            read_f32(packet, 16),
+            read_f32(packet, 20),
+            flags & FLAG_BALL != 0,
+        ),
+        ball_seen: now.checked_sub(Duration::from_millis(age)).unwrap_or(now),
+        ball_confidence: packet[30] as f32 / 255.,
+        started: flags & FLAG_STARTED != 0,
+        stuck: flags & FLAG_STUCK != 0,
+        phase,
+        mirrored: flags & FLAG_MIRRORED != 0,
+        kicked_off: flags & FLAG_KICKED_OFF != 0,
+        cost: read_f32(packet, 24),
+        last_seen: now,
+    })
+}
+
+// whether to take the teammate's side when the two differ: whichever robot switched away from the
+// side both last agreed on is followed, and without an agreement, as after a reboot, a robot in the
+// middle of a game keeps its side and otherwise the lower id does
+fn follow_teammate(teammate: &Teammate, id: u8, agreed: Option<bool>, playing: bool) -> bool {
+    if let Some(agreed) = agreed {
+        return teammate.mirrored != agreed;
+    }
+
+    if teammate.phase.active() != playing {
+        teammate.phase.active()
+    } else {
+        teammate.id < id
+    }
+}
+
+async fn ourselves(id: u8) -> Teammate {
+    let game = read_mutex!(GAME_MUTEX);
+
+    Teammate {
+        id,
+        role: read_mutex!(ROLE_MUTEX),
+        position: read_mutex!(COORDINATE_MUTEX),
+        heading: read_mutex!(HEADING_MUTEX),
+        ball: read_mutex!(BALL_MUTEX),
+        ball_seen: read_mutex!(BALL_SEEN_MUTEX),
+        ball_confidence: world::confidence().await,
+        started: get_config!(started),
+        stuck: read_mutex!(STUCK_MUTEX),
+        phase: game.phase,
+        mirrored: side::mirrored().await,
+        kicked_off: game.kicked_off,
+        cost: role::cost().await,
+        last_seen: Instant::now(),
+    }
+}
+
+#[embassy_executor::task]
+async fn team_task(stack: &'static Stack<NetDriver<'static>>) {
+    let mut rx_meta = [PacketMetadata::EMPTY; 4];
+    let mut rx_buffer = [0; 256];
+    let mut tx_meta = [PacketMetadata::EMPTY; 4];
+    let mut tx_buffer = [0; 256];
+    let mut buffer = [0; 64];
+
+    let mut socket = UdpSocket::new(
+        stack,
+        &mut rx_meta,
+        &mut rx_buffer,
+        &mut tx_meta,
+        &mut tx_buffer,
+    );
+
+    if socket.bind(TEAM_PORT).is_err() {
+        warn!("Failed to bind team socket");
+        return;
+    }
+
+    let address = match stack.config_v4() {
+        Some(config) => config.address.address().0,
+        None => return,
+    };
+    let id = address[3];
+    let broadcast = IpEndpoint::new(
+        Ipv4Address::new(address[0], address[1], address[2], 255).into(),
+        TEAM_PORT,
+    );
+
+    let mut last_sent = Instant::now();
+    let mut agreed: Option<bool> = None;
+    let mut last_received = Instant::now();
+
+    loop {
+        let remaining = TEAM_INTERVAL.saturating_sub(last_sent.elapsed().as_millis());
+
+        let event = select(
+            Timer::after_millis(remaining),
+            socket.recv_from(&mut buffer),
+        )
+        .await;
+
+        match event {
+            Either::First(_) => {
+                last_sent = Instant::now();
+                let packet = encode(&ourselves(id).await);
+                if socket.send_to(&packet, broadcast).await.is_err() {
+                    warn!("Failed to send team packet");
+                }
+            }
+            Either::Second(Ok((len, _))) => {
+                if let Some(teammate) = decode(&buffer[..len]).filter(|data| data.id != id) {
+                    // a teammate that has been gone for a while may have rebooted onto another side
+                    if last_received.elapsed().as_millis() > LINK_TIMEOUT {
+                        agreed = None;
+                    }
+                    last_received = Instant::now();
+
+                    // both robots always play the same way round, so switching on one switches both
+                    let mirrored = side::mirrored().await;
+                    if teammate.mirrored != mirrored {
+                        let playing = read_mutex!(GAME_MUTEX).phase.active();
+                        if follow_teammate(&teammate, id, agreed, playing) {
+                            side::set(teammate.mirrored).await;
+                        } else if agreed.is_none() {
+                            warn!("Teammate is on the other side, waiting for it to switch");
+                        }
+                    }
+
+                    // positions from a teammate still on the other side would be mirrored
+                    if teammate.mirrored != side::mirrored().await {
+                        continue;
+                    }
+                    agreed = Some(teammate.mirrored);
+
+                    write_mutex!(TEAMMATE_MUTEX, Some(teammate));
+                    debug_variable!("teammate", teammate.id);
+                    debug_variable!("teammate cost", teammate.cost);
+                }
+            }
+            Either::Second(Err(_)) => {}
+        }
+    }
+}
+
+pub async fn init(spawner: &Spawner, stack: &'static Stack<NetDriver<'static>>) {
+    info!("Starting team link on port {}", TEAM_PORT);
+
+    spawner.must_spawn(team_task(stack));
+}
@@ -1,4 +1,4 @@
-use crate::network::{SERVER_THREADS, WIFI_NETWORK, WIFI_PASSWORD};
+use crate::network::{SOCKETS, WIFI_NETWORK, WIFI_PASSWORD};
 use cyw43::{Control, NetDriver, PowerManagementMode::PowerSave, Runner, State};
 use cyw43_pio::PioSpi;
 use defmt::{info, warn};
@@ -75,7 +75,7 @@ pub async fn init(
             gateway: None,
             dns_servers: Vec::new(),
         }),
-        make_static!(StackResources::<SERVER_THREADS>::new()),
+        make_static!(StackResources::<SOCKETS>::new()),
         RoscRng.gen(),
     ));
 
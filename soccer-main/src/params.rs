@@ -0,0 +1,130 @@
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex};
+#[cfg(feature = "network")]
+use heapless::{FnvIndexMap, String};
+
+#[cfg(feature = "network")]
+pub type ParamMap = FnvIndexMap<&'static str, String<16>, 64>;
+
+macro_rules! init_params {
+    ($($name:ident: $type:ty = $value:expr => $range:expr),*,) => {
+        pub static PARAMS: Mutex<CriticalSectionRawMutex, Params> = Mutex::new(Params {
+            $(
+                $name: $value,
+            )*
+        });
+
+        #[derive(Clone, Copy)]
+        pub struct Params {
+            $(
+                pub $name: $type,
+            )*
+        }
+
+        impl Default for Params {
+            fn default() -> Self {
+                Self {
+                    $(
+                        $name: $value,
+                    )*
+                }
+            }
+        }
+
+        #[cfg(feature = "network")]
+        pub async fn get_params() -> ParamMap {
+            let params = PARAMS.lock().await;
+            let mut map = ParamMap::new();
+            $(
+                let value = heapless::format!("{}", params.$name).unwrap_or_default();
+                let _ = map.insert(stringify!($name), value);
+            )*
+            map
+        }
+
+        // returns false for unknown names and values that do not parse as the parameter's type or
+        // fall outside its range, which also turns away anything that is not finite
+        #[cfg(feature = "network")]
+        pub async fn set_param(name: &str, value: &str) -> bool {
+            let mut params = PARAMS.lock().await;
+            match name {
+                $(
+                    stringify!($name) => match value.parse::<$type>() {
+                        Ok(value) if ($range).contains(&value) => {
+                            params.$name = value;
+                            true
+                        }
+                        _ => false,
+                    },
+                )*
+                _ => false,
+            }
+        }
+    };
+}
+
+// every parameter has the range it may be set to over the network, with the defaults inside it
+init_params! {
+    // strategy selection
+    no_ball_duration: u64 = 500 => 1..=10000,
+    goalie_no_ball_duration: u64 = 200 => 1..=10000,
+    goalie_attack_duration: u64 = 6000 => 1..=60000,
+    striker_distance: f32 = 30. => 1. ..=80.,
+    intercept_speed: f32 = 25. => 1. ..=500.,
+    intercept_distance: f32 = 30. => 1. ..=200.,
+    wall_distance: f32 = 10. => 1. ..=50.,
+    wall_range: f32 = 50. => 1. ..=200.,
+    corner_distance: f32 = 10. => 1. ..=50.,
+    corner_range: f32 = 50. => 1. ..=200.,
+    far_distance: f32 = 50. => 1. ..=200.,
+    play_area_margin: f32 = 5. => 0. ..=30.,
+    // attack
+    attack_aligned_threshold: f32 = 1.5 => 0.1..=20.,
+    attack_captured_duration: u64 = 250 => 1..=5000,
+    attack_initial_change: f32 = 35. => 0. ..=200.,
+    attack_gradual_change: f32 = 250. => 0. ..=1000.,
+    attack_approach_angle: f32 = 15. => 1. ..=90.,
+    // bounds, where the move distance is effectively the speed
+    bounds_move_distance: f32 = 10. => 1. ..=50.,
+    // clear
+    clear_push_distance: f32 = 20. => 1. ..=100.,
+    clear_push_duration: u64 = 250 => 1..=5000,
+    clear_wait_distance: f32 = 20. => 1. ..=100.,
+    clear_wait_duration: u64 = 2000 => 1..=10000,
+    clear_approach_angle: f32 = 15. => 1. ..=90.,
+    // defence
+    defence_last_push_threshold: u64 = 100 => 1..=5000,
+    // goalie
+    goalie_distance: f32 = 3. => 0. ..=30.,
+    goalie_movement_threshold_x: f32 = 7.5 => 0.1..=50.,
+    goalie_movement_threshold_y: f32 = 7.5 => 0.1..=50.,
+    goalie_changed_threshold: u64 = 2500 => 1..=30000,
+    goalie_min_x: f32 = 10. => 0. ..=60.,
+    goalie_shot_speed: f32 = 20. => 1. ..=500.,
+    goalie_shot_margin: f32 = 10. => 0. ..=60.,
+    goalie_clear_speed: f32 = 10. => 0.1..=100.,
+    goalie_clear_distance: f32 = 15. => 1. ..=60.,
+    goalie_clear_radius: f32 = 15. => 1. ..=60.,
+    goalie_approach_angle: f32 = 15. => 1. ..=90.,
+    // intercept
+    intercept_robot_speed: f32 = 120. => 1. ..=500.,
+    intercept_robot_acceleration: f32 = 250. => 1. ..=2000.,
+    intercept_ball_deceleration: f32 = 30. => 1. ..=500.,
+    intercept_time_step: f32 = 0.05 => 0.01..=1.,
+    intercept_time_max: f32 = 2. => 0.1..=10.,
+    intercept_retarget_distance: f32 = 3. => 0.1..=50.,
+    // kickoff
+    kickoff_placed_distance: f32 = 3. => 0.5..=30.,
+    // no ball
+    no_ball_distance: f32 = 35. => 1. ..=150.,
+    no_ball_goalie_distance: f32 = 5. => 0. ..=50.,
+    no_ball_check_distance: f32 = 5. => 0.5..=50.,
+    no_ball_threshold_x: f32 = 10. => 0.5..=100.,
+    no_ball_threshold_y: f32 = 10. => 0.5..=100.,
+    // wall
+    wall_approach_distance: f32 = 15. => 1. ..=50.,
+    wall_approach_tolerance: f32 = 4. => 0.5..=30.,
+    wall_push_distance: f32 = 3. => 0. ..=30.,
+    wall_flick_angle: f32 = 60. => 1. ..=120.,
+    wall_flick_duration: u64 = 300 => 1..=5000,
+    wall_retreat_duration: u64 = 500 => 1..=5000,
+}
@@ -1,10 +1,8 @@
 use crate::{
     modules::{COORDINATE_SIGNAL, HEADING_SIGNAL},
-    strategy::Data,
+    strategy::{machine::Behaviour, Data},
 };
 
-const MOVE_DISTANCE: f32 = 10.; // effectively speed
-
 #[derive(Default)]
 pub struct BoundsState {
     pub was_left: bool,
@@ -13,25 +11,32 @@ pub struct BoundsState {
     pub was_back: bool,
 }
 
+impl Behaviour for BoundsState {
+    async fn run(&mut self, data: Data) {
+        run(data, self).await;
+    }
+}
+
 pub async fn run(data: Data, state: &mut BoundsState) {
+    let params = data.params;
     let (x, y, _) = data.coordinates;
     let (front, left, right, back) = data.lines;
 
     let new_x = if left || state.was_left {
         state.was_left = true;
-        x + MOVE_DISTANCE
+        x + params.bounds_move_distance
     } else if right || state.was_right {
         state.was_right = true;
-        x - MOVE_DISTANCE
+        x - params.bounds_move_distance
     } else {
         x
     };
     let new_y = if front || state.was_front {
         state.was_front = true;
-        y + MOVE_DISTANCE
+        y + params.bounds_move_distance
     } else if back || state.was_right {
         state.was_back = true;
-        y - MOVE_DISTANCE
+        y - params.bounds_move_distance
     } else {
         y
     };
@@ -1,15 +1,9 @@
 use crate::{
     constants::BALLCAP_DISTANCE,
-    modules::{COORDINATE_SIGNAL, HEADING_SIGNAL},
-    strategy::Data,
+    modules::{orbit::Orbit, COORDINATE_SIGNAL, HEADING_SIGNAL},
+    strategy::{machine::Behaviour, Data},
 };
 use embassy_time::Instant;
-use num_traits::Float;
-
-const PUSH_DISTANCE: f32 = 20.;
-const PUSH_DURATION: u64 = 250;
-const WAIT_DISTANCE: f32 = 20.;
-const WAIT_DURATION: u64 = 2000;
 
 pub struct ClearState {
     pushed: bool,
@@ -17,6 +11,7 @@ pub struct ClearState {
     waiting: bool,
     waiting_time: Instant,
     moving_x: bool,
+    orbit: Orbit,
 }
 
 impl Default for ClearState {
@@ -27,24 +22,32 @@ impl Default for ClearState {
             waiting: false,
             waiting_time: Instant::now(),
             moving_x: false,
+            orbit: Orbit::default(),
         }
     }
 }
 
+impl Behaviour for ClearState {
+    async fn run(&mut self, data: Data) {
+        run(data, self).await;
+    }
+}
+
 pub async fn run(data: Data, state: &mut ClearState) {
+    let params = data.params;
     let (x, y, _) = data.coordinates;
     let (bx, by, _) = data.ball;
 
     HEADING_SIGNAL.signal(0.);
 
-    if state.pushed && state.pushed_time.elapsed().as_millis() > PUSH_DURATION {
+    if state.pushed && state.pushed_time.elapsed().as_millis() > params.clear_push_duration {
         if !state.waiting {
             state.waiting = true;
             state.waiting_time = Instant::now();
         }
 
-        if state.waiting && state.waiting_time.elapsed().as_millis() < WAIT_DURATION {
-            COORDINATE_SIGNAL.signal((bx, by + WAIT_DISTANCE));
+        if state.waiting && state.waiting_time.elapsed().as_millis() < params.clear_wait_duration {
+            COORDINATE_SIGNAL.signal((bx, by + params.clear_wait_distance));
             return;
         } else {
             state.pushed = false;
@@ -52,16 +55,23 @@ pub async fn run(data: Data, state: &mut ClearState) {
         }
     }
 
-    let new_x = bx;
-    let new_y = if (!state.pushed && y - by < PUSH_DISTANCE - 2.)
-        || (!state.moving_x && (x - bx).abs() > BALLCAP_DISTANCE)
-        || (state.moving_x && (x - bx).abs() > BALLCAP_DISTANCE / 2.)
+    // come around to the far side of the ball and push it back towards our half
+    let (target, remaining) = state.orbit.next(
+        (bx, by),
+        (x, y),
+        params.clear_push_distance + BALLCAP_DISTANCE,
+        180.,
+    );
+
+    let (new_x, new_y) = if (!state.pushed && y - by < params.clear_push_distance - 2.)
+        || (!state.moving_x && remaining > params.clear_approach_angle)
+        || (state.moving_x && remaining > params.clear_approach_angle / 2.)
     {
         state.moving_x = true;
-        by - PUSH_DISTANCE - BALLCAP_DISTANCE
+        target
     } else {
         state.moving_x = false;
-        by - BALLCAP_DISTANCE - 3.
+        (bx, by - BALLCAP_DISTANCE - 3.)
     };
 
     if !state.moving_x && !state.pushed {
@@ -1,26 +1,33 @@
 use crate::{
-    modules::HEADING_SIGNAL,
-    strategy::{Data, CLEARANCE_Y, COORDINATE_SIGNAL},
+    modules::{orbit::Orbit, HEADING_SIGNAL},
+    strategy::{machine::Behaviour, Data, CLEARANCE_Y, COORDINATE_SIGNAL},
 };
 use embassy_time::Instant;
 
-const LAST_PUSH_THRESHOLD: u64 = 100;
-
 pub struct DefenceState {
     pub last_push: Instant,
+    pub orbit: Orbit,
 }
 
 impl Default for DefenceState {
     fn default() -> Self {
         Self {
             last_push: Instant::from_millis(0),
+            orbit: Orbit::default(),
         }
     }
 }
 
+impl Behaviour for DefenceState {
+    async fn run(&mut self, data: Data) {
+        run(data, self).await;
+    }
+}
+
 pub async fn run(data: Data, state: &mut DefenceState) {
+    let params = data.params;
     let (bx, by, _) = data.ball;
-    let (_, y, _) = data.coordinates;
+    let (x, y, _) = data.coordinates;
 
     HEADING_SIGNAL.signal(0.);
 
@@ -28,9 +35,13 @@ pub async fn run(data: Data, state: &mut DefenceState) {
         state.last_push = Instant::now();
     }
 
-    if state.last_push.elapsed().as_millis() < LAST_PUSH_THRESHOLD {
+    if state.last_push.elapsed().as_millis() < params.defence_last_push_threshold {
         COORDINATE_SIGNAL.signal((bx, by + 1.5));
     } else {
-        COORDINATE_SIGNAL.signal((bx, by - CLEARANCE_Y - 2.));
+        // block in front of the ball, going around it rather than through it to get there
+        state
+            .orbit
+            .run((bx, by), (x, y), CLEARANCE_Y + 2., 180., false)
+            .await;
     }
 }
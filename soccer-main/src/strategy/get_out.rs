@@ -1,11 +1,17 @@
 use crate::{
     modules::HEADING_SIGNAL,
-    strategy::{Data, COORDINATE_SIGNAL, FIELD_LENGTH, FIELD_MARGIN_Y},
+    strategy::{machine::Behaviour, Data, COORDINATE_SIGNAL, FIELD_LENGTH, FIELD_MARGIN_Y},
 };
 
 #[derive(Default)]
 pub struct GetOutState {}
 
+impl Behaviour for GetOutState {
+    async fn run(&mut self, data: Data) {
+        run(data, self).await;
+    }
+}
+
 pub async fn run(data: Data, _: &mut GetOutState) {
     let (x, y, _) = data.coordinates;
 
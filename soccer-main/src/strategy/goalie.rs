@@ -1,22 +1,22 @@
 use crate::{
-    modules::HEADING_SIGNAL,
-    strategy::{Data, COORDINATE_SIGNAL, FIELD_LENGTH, FIELD_MARGIN, FIELD_MARGIN_Y, FIELD_WIDTH},
-    utils::{clamp_angle, construct_vector},
+    constants::{BALLCAP_DISTANCE, GOAL_WIDTH},
+    modules::{field::PENALTY_AREA, orbit::Orbit, CLEARING_MUTEX, HEADING_SIGNAL},
+    strategy::{
+        machine::Behaviour, Data, COORDINATE_SIGNAL, FIELD_LENGTH, FIELD_MARGIN, FIELD_MARGIN_Y,
+        FIELD_WIDTH,
+    },
+    utils::{clamp_angle, construct_vector, debug::debug_variable, write_mutex},
 };
 use embassy_time::Instant;
+use nalgebra::Vector2;
 use num_traits::{clamp, Float};
 
-const GOALIE_DISTANCE: f32 = 3.;
-const MOVEMENT_THRESHOLD_X: f32 = 7.5;
-const MOVEMENT_THRESHOLD_Y: f32 = 7.5;
-const CHANGED_THRESHOLD: u64 = 2500;
-const MIN_X: f32 = 10.;
-
 pub struct GoalieState {
     pub last_bx: f32,
     pub last_by: f32,
     pub last_changed: Instant,
     pub pushing: bool,
+    pub orbit: Orbit,
 }
 
 impl Default for GoalieState {
@@ -26,11 +26,23 @@ impl Default for GoalieState {
             last_by: -999.,
             last_changed: Instant::now(),
             pushing: false,
+            orbit: Orbit::default(),
         }
     }
 }
 
+impl Behaviour for GoalieState {
+    async fn run(&mut self, data: Data) {
+        run(data, self).await;
+    }
+
+    async fn exit(&mut self) {
+        write_mutex!(CLEARING_MUTEX, false);
+    }
+}
+
 pub async fn run(data: Data, state: &mut GoalieState) {
+    let params = data.params;
     let (bx, by, _bok) = data.ball;
     let (x, y, ok) = data.coordinates;
 
@@ -41,20 +53,63 @@ pub async fn run(data: Data, state: &mut GoalieState) {
         return;
     }
 
-    if (bx - state.last_bx).abs() > MOVEMENT_THRESHOLD_X
-        || (by - state.last_by).abs() > MOVEMENT_THRESHOLD_Y
+    if (bx - state.last_bx).abs() > params.goalie_movement_threshold_x
+        || (by - state.last_by).abs() > params.goalie_movement_threshold_y
     {
         state.last_bx = bx;
         state.last_by = by;
         state.last_changed = Instant::now();
     }
 
-    if state.last_changed.elapsed().as_millis() > CHANGED_THRESHOLD {
+    if state.last_changed.elapsed().as_millis() > params.goalie_changed_threshold {
         state.pushing = true;
     }
 
     let mut new_x = bx;
-    let new_y = FIELD_LENGTH - FIELD_MARGIN_Y - GOALIE_DISTANCE;
+    let new_y = FIELD_LENGTH - FIELD_MARGIN_Y - params.goalie_distance;
+
+    let (vx, vy) = data.ball_velocity;
+    let (ball_speed, _) = construct_vector(vx, vy);
+
+    // a slow ball in our penalty area is ours to clear
+    let in_penalty = PENALTY_AREA.contains(Vector2::new(bx, by));
+    let clearing = ball_speed < params.goalie_clear_speed && in_penalty;
+    write_mutex!(CLEARING_MUTEX, clearing);
+
+    if clearing {
+        debug_variable!("goalie mode", "clear");
+
+        let (target, remaining) =
+            state
+                .orbit
+                .next((bx, by), (x, y), params.goalie_clear_radius, 0.);
+        if remaining < params.goalie_approach_angle || y > by + BALLCAP_DISTANCE / 2. {
+            COORDINATE_SIGNAL.signal((bx, by - params.goalie_clear_distance));
+        } else {
+            COORDINATE_SIGNAL.signal(target);
+        }
+        return;
+    }
+
+    // block where a shot will cross the goalie line, ahead of the angle bisector
+    if vy > params.goalie_shot_speed && by < new_y {
+        let cross_x = bx + vx * (new_y - by) / vy;
+
+        if (cross_x - FIELD_WIDTH / 2.).abs() < GOAL_WIDTH / 2. + params.goalie_shot_margin {
+            debug_variable!("goalie mode", "block");
+            debug_variable!("goalie shot x", cross_x);
+
+            new_x = clamp(
+                cross_x,
+                FIELD_MARGIN + params.goalie_min_x,
+                FIELD_WIDTH - FIELD_MARGIN - params.goalie_min_x,
+            );
+            COORDINATE_SIGNAL.signal((new_x, new_y));
+            return;
+        }
+    }
+
+    debug_variable!("goalie mode", "bisect");
 
     if ok {
         let (_, angle_l) = construct_vector(
@@ -78,8 +133,8 @@ pub async fn run(data: Data, state: &mut GoalieState) {
 
         new_x = clamp(
             bx + mag * sin,
-            FIELD_MARGIN + MIN_X,
-            FIELD_WIDTH - FIELD_MARGIN - MIN_X,
+            FIELD_MARGIN + params.goalie_min_x,
+            FIELD_WIDTH - FIELD_MARGIN - params.goalie_min_x,
         );
     }
 
@@ -0,0 +1,126 @@
+use crate::{
+    constants::{BALLCAP_DISTANCE, FIELD_LENGTH, FIELD_MARGIN, FIELD_WIDTH},
+    modules::{COORDINATE_SIGNAL, HEADING_SIGNAL},
+    params::Params,
+    strategy::{machine::Behaviour, Data},
+    utils::{construct_vector, debug::debug_variable},
+};
+use num_traits::Float;
+
+#[derive(Default)]
+pub struct InterceptState {
+    pub target: Option<(f32, f32)>,
+}
+
+// time for the robot to cover a distance from rest with its speed and acceleration limits
+fn travel_time(distance: f32, params: &Params) -> f32 {
+    let speed = params.intercept_robot_speed;
+    let acceleration = params.intercept_robot_acceleration;
+
+    let ramp = speed * speed / (2. * acceleration);
+    if distance < ramp {
+        (2. * distance / acceleration).sqrt()
+    } else {
+        distance / speed + speed / (2. * acceleration)
+    }
+}
+
+// where the rolling ball will be after some time, slowing down until it stops
+fn predict(ball: (f32, f32), velocity: (f32, f32), time: f32, params: &Params) -> (f32, f32) {
+    let (speed, _) = construct_vector(velocity.0, velocity.1);
+    if speed < f32::EPSILON {
+        return ball;
+    }
+
+    let time = time.min(speed / params.intercept_ball_deceleration);
+    let distance = speed * time - params.intercept_ball_deceleration * time * time / 2.;
+
+    (
+        ball.0 + velocity.0 / speed * distance,
+        ball.1 + velocity.1 / speed * distance,
+    )
+}
+
+// returns the earliest point on the ball's path that the robot can reach before the ball does
+pub fn intercept(
+    position: (f32, f32),
+    ball: (f32, f32),
+    velocity: (f32, f32),
+    params: &Params,
+) -> Option<((f32, f32), f32)> {
+    let mut time = 0.;
+
+    while time <= params.intercept_time_max {
+        let (bx, by) = predict(ball, velocity, time, params);
+
+        // stop looking once the ball would have left the field
+        if !(0. ..=FIELD_WIDTH).contains(&bx) || !(0. ..=FIELD_LENGTH).contains(&by) {
+            return None;
+        }
+
+        // the robot needs to be on the goal side of the ball to take it
+        let target = (bx, by + BALLCAP_DISTANCE);
+        let (distance, _) = construct_vector(target.0 - position.0, target.1 - position.1);
+
+        if travel_time(distance, params) <= time {
+            return Some((target, time));
+        }
+
+        time += params.intercept_time_step;
+    }
+
+    None
+}
+
+impl Behaviour for InterceptState {
+    async fn run(&mut self, data: Data) {
+        run(data, self).await;
+    }
+}
+
+pub async fn run(data: Data, state: &mut InterceptState) {
+    let params = data.params;
+    let (x, y, _) = data.coordinates;
+    let (bx, by, _) = data.ball;
+
+    HEADING_SIGNAL.signal(0.);
+
+    let target = match intercept((x, y), (bx, by), data.ball_velocity, &params) {
+        Some((target, time)) => {
+            debug_variable!("intercept time", time);
+            target
+        }
+        None => {
+            // head for where the ball comes to rest when it cannot be caught on the way
+            let (rx, ry) = predict(
+                (bx, by),
+                data.ball_velocity,
+                params.intercept_time_max,
+                &params,
+            );
+            (rx, ry + BALLCAP_DISTANCE)
+        }
+    };
+
+    let target = (
+        target.0.clamp(FIELD_MARGIN, FIELD_WIDTH - FIELD_MARGIN),
+        target.1.clamp(FIELD_MARGIN, FIELD_LENGTH - FIELD_MARGIN),
+    );
+
+    // hold on to the previous target while the estimate only jitters around it
+    let target = match state.target {
+        Some(last)
+            if construct_vector(target.0 - last.0, target.1 - last.1).0
+                < params.intercept_retarget_distance =>
+        {
+            last
+        }
+        _ => target,
+    };
+
+    debug_variable!("intercept x", target.0);
+    debug_variable!("intercept y", target.1);
+
+    state.target = Some(target);
+    COORDINATE_SIGNAL.signal(target);
+}
@@ -0,0 +1,84 @@
+use crate::{
+    config::get_config,
+    modules::{
+        game::{Phase, GAME_MUTEX},
+        trajectory,
+        trajectory::Waypoint,
+        HEADING_SIGNAL,
+    },
+    strategy::{machine::Behaviour, Data, COORDINATE_SIGNAL},
+    utils::{construct_vector, debug::debug_variable, read_mutex},
+};
+use defmt::info;
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex};
+use heapless::Vec;
+
+const MAX_OPENING: usize = 8;
+
+// the move played straight from the kickoff spot when we kick off, left empty to just play on
+static OPENING: Mutex<CriticalSectionRawMutex, Vec<Waypoint, MAX_OPENING>> = Mutex::new(Vec::new());
+
+pub async fn add_opening_waypoint(waypoint: Waypoint) -> bool {
+    let mut opening = OPENING.lock().await;
+    opening.push(waypoint).is_ok()
+}
+
+pub async fn clear_opening() {
+    let mut opening = OPENING.lock().await;
+    opening.clear();
+}
+
+#[derive(Default)]
+pub struct KickoffState {
+    pub placed: bool,
+}
+
+impl Behaviour for KickoffState {
+    async fn run(&mut self, data: Data) {
+        run(data, self).await;
+    }
+
+    // the machine leaves the kickoff spot once the kickoff is taken, which is when the opening
+    // move starts if it is ours
+    async fn exit(&mut self) {
+        let game = read_mutex!(GAME_MUTEX);
+        if game.phase != Phase::KickoffAttacking || !game.kicked_off {
+            return;
+        }
+
+        let opening = OPENING.lock().await.clone();
+        if opening.is_empty() {
+            return;
+        }
+
+        info!("Playing opening with {} waypoints", opening.len());
+        trajectory::clear().await;
+        for waypoint in opening {
+            trajectory::add_waypoint(waypoint).await;
+        }
+        trajectory::start().await;
+    }
+}
+
+pub async fn run(data: Data, state: &mut KickoffState) {
+    let params = data.params;
+    let (x, y, ok) = data.coordinates;
+
+    let (tx, ty) = if data.goalie {
+        get_config!(kickoff_goalie)
+    } else {
+        get_config!(kickoff_striker)
+    };
+
+    HEADING_SIGNAL.signal(0.);
+    COORDINATE_SIGNAL.signal((tx, ty));
+
+    let (distance, _) = construct_vector(tx - x, ty - y);
+    let placed = ok && distance < params.kickoff_placed_distance;
+    if placed && !state.placed {
+        info!("In kickoff position");
+    }
+    state.placed = placed;
+
+    debug_variable!("kickoff placed", placed);
+}
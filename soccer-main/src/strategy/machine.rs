@@ -0,0 +1,104 @@
+use crate::strategy::{Data, Strategy};
+use embassy_time::Instant;
+
+// a behaviour is entered with fresh state, run on every update while active and exited when the
+// machine moves on to another one
+pub trait Behaviour: Default {
+    async fn enter(&mut self, _data: &Data) {
+        *self = Self::default();
+    }
+
+    async fn run(&mut self, data: Data);
+
+    async fn exit(&mut self) {}
+}
+
+// rules are checked in order and the first guard that passes picks the strategy, and unless the
+// rule preempts, the current strategy is kept until it has not been wanted for its dwell time
+pub struct Rule<C> {
+    pub strategy: Strategy,
+    pub guard: fn(&C) -> bool,
+    pub reason: &'static str,
+    pub preempt: bool,
+}
+
+impl<C> Rule<C> {
+    pub const fn new(strategy: Strategy, guard: fn(&C) -> bool, reason: &'static str) -> Self {
+        Self {
+            strategy,
+            guard,
+            reason,
+            preempt: false,
+        }
+    }
+
+    pub const fn preempt(strategy: Strategy, guard: fn(&C) -> bool, reason: &'static str) -> Self {
+        Self {
+            strategy,
+            guard,
+            reason,
+            preempt: true,
+        }
+    }
+}
+
+pub struct Transition {
+    pub from: Strategy,
+    pub to: Strategy,
+    pub reason: &'static str,
+    pub time: Instant,
+}
+
+pub struct Machine {
+    current: Strategy,
+    reason: &'static str,
+    last_wanted: Instant,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Self {
+            current: Strategy::None,
+            reason: "initial",
+            last_wanted: Instant::from_millis(0),
+        }
+    }
+}
+
+impl Machine {
+    pub fn current(&self) -> Strategy {
+        self.current
+    }
+
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+
+    pub fn update<C>(&mut self, rules: &[Rule<C>], context: &C) -> Option<Transition> {
+        let rule = rules.iter().find(|rule| (rule.guard)(context))?;
+        let now = Instant::now();
+
+        if rule.strategy == self.current {
+            self.reason = rule.reason;
+            self.last_wanted = now;
+            return None;
+        }
+
+        if !rule.preempt && (now - self.last_wanted).as_millis() < self.current.dwell() {
+            return None;
+        }
+
+        let transition = Transition {
+            from: self.current,
+            to: rule.strategy,
+            reason: rule.reason,
+            time: now,
+        };
+
+        self.current = rule.strategy;
+        self.reason = rule.reason;
+        self.last_wanted = now;
+
+        Some(transition)
+    }
+}
@@ -1,14 +1,29 @@
 use crate::{
-    config::get_config,
     constants::{
         BALLCAP_DISTANCE, BALLCAP_WIDTH, CLEARANCE_Y, FIELD_LENGTH, FIELD_MARGIN, FIELD_MARGIN_X,
         FIELD_MARGIN_Y, FIELD_WIDTH,
     },
     hardware::{BALL_SIGNAL, LINE_SIGNAL},
-    modules::{BALL_CHANGED, BALL_MUTEX, COORDINATE_MUTEX, COORDINATE_SIGNAL, UNIGNORE_SIGNAL},
+    modules::{
+        field::PENALTY_AREA,
+        game::{Game, GAME_MUTEX},
+        world, Role, BALL_CHANGED, BALL_MUTEX, BALL_VELOCITY_MUTEX, COORDINATE_MUTEX,
+        COORDINATE_SIGNAL, ROLE_MUTEX, STUCK_MUTEX, TRAJECTORY_MUTEX, UNIGNORE_SIGNAL,
+    },
+    params::{Params, PARAMS},
     strategy::{
-        attack::AttackState, bounds::BoundsState, clear::ClearState, defence::DefenceState,
-        get_out::GetOutState, goalie::GoalieState, no_ball::NoBallState,
+        attack::AttackState,
+        bounds::BoundsState,
+        clear::ClearState,
+        defence::DefenceState,
+        get_out::GetOutState,
+        goalie::GoalieState,
+        intercept::InterceptState,
+        kickoff::KickoffState,
+        machine::{Behaviour, Machine, Rule},
+        no_ball::NoBallState,
+        script::ScriptState,
+        wall::WallState,
     },
     utils::{construct_vector, debug::debug_variable, read_mutex},
 };
@@ -17,6 +32,7 @@ use embassy_executor::Spawner;
 use embassy_futures::select::{select3, Either3};
 use embassy_sync::pubsub::WaitResult;
 use embassy_time::Instant;
+use nalgebra::Vector2;
 use num_traits::{clamp, Float};
 
 pub mod attack;
@@ -25,14 +41,16 @@ pub mod clear;
 pub mod defence;
 pub mod get_out;
 pub mod goalie;
+pub mod intercept;
+pub mod kickoff;
+pub mod machine;
 pub mod no_ball;
+pub mod script;
+pub mod wall;
 
 const STRATEGY_DURATION: u64 = 15;
 const BOUNDS_DURATION: u64 = 100;
-const NO_BALL_DURATION: u64 = 500;
-const GOALIE_NO_BALL_DURATION: u64 = 200;
-const GOALIE_ATTACK_DURATION: u64 = 6000;
-const STRIKER_DISTANCE: f32 = 30.;
+const WALL_DURATION: u64 = 300;
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum Strategy {
@@ -43,17 +61,238 @@ pub enum Strategy {
     Defence,
     Goalie,
     GetOut,
+    Intercept,
+    Kickoff,
     NoBall,
+    Script,
+    Wall,
 }
 
-#[derive(Default)]
+impl Strategy {
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::None => "none",
+            Strategy::Attack => "attack",
+            Strategy::Bounds => "bounds",
+            Strategy::Clear => "clear",
+            Strategy::Defence => "defence",
+            Strategy::Goalie => "goalie",
+            Strategy::GetOut => "get_out",
+            Strategy::Intercept => "intercept",
+            Strategy::Kickoff => "kickoff",
+            Strategy::NoBall => "no_ball",
+            Strategy::Script => "script",
+            Strategy::Wall => "wall",
+        }
+    }
+
+    // how long a strategy must have gone unwanted before the machine may leave it
+    pub fn dwell(self) -> u64 {
+        match self {
+            Strategy::None | Strategy::Kickoff | Strategy::NoBall => 0,
+            Strategy::Bounds => BOUNDS_DURATION,
+            Strategy::Wall => WALL_DURATION,
+            _ => STRATEGY_DURATION,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy)]
 pub struct Data {
     pub ball: (f32, f32, bool),
+    pub ball_velocity: (f32, f32),
     pub coordinates: (f32, f32, bool),
     pub captured: bool,
     pub lines: (bool, bool, bool, bool),
     pub goalie: bool,
     pub is_camera: bool,
+    pub params: Params,
+}
+
+struct Context {
+    data: Data,
+    game: Game,
+    ball: (f32, f32),
+    dist: f32,
+    ball_speed: f32,
+    ball_lost: bool,
+    goalie_attacking: bool,
+    stuck: bool,
+    trajectory: bool,
+    script: bool,
+}
+
+fn game_stopped(c: &Context) -> bool {
+    !c.game.phase.active()
+}
+
+fn waiting_for_kickoff(c: &Context) -> bool {
+    c.game.phase.kickoff() && !c.game.kicked_off
+}
+
+fn stuck(c: &Context) -> bool {
+    c.stuck
+}
+
+fn following_trajectory(c: &Context) -> bool {
+    c.trajectory
+}
+
+fn script_active(c: &Context) -> bool {
+    c.script
+}
+
+fn on_line(c: &Context) -> bool {
+    let lines = c.data.lines;
+    lines.0 || lines.1 || lines.2 || lines.3
+}
+
+fn ball_in_corner(c: &Context) -> bool {
+    let (_, y, ok) = c.data.coordinates;
+    let (bx, by) = c.ball;
+    let params = &c.data.params;
+
+    ok && c.dist < params.corner_range
+        && !c.ball_lost
+        && !(FIELD_MARGIN_X..=FIELD_WIDTH - FIELD_MARGIN_X).contains(&bx)
+        && by < FIELD_MARGIN + params.corner_distance
+        && by < y
+}
+
+fn goalie_ball_in_corner(c: &Context) -> bool {
+    c.data.goalie && ball_in_corner(c)
+}
+
+// a slow ball in our penalty area is the goalie's to clear, even once it is behind the goalie
+fn goalie_clearing(c: &Context) -> bool {
+    let (bx, by) = c.ball;
+    c.data.goalie
+        && !c.ball_lost
+        && c.ball_speed < c.data.params.goalie_clear_speed
+        && PENALTY_AREA.contains(Vector2::new(bx, by))
+}
+
+// against a side wall, or in a corner along it, there is no room to come around the ball, so the
+// striker takes it along the wall instead, as long as the spot behind the ball is within its
+// bounds, while balls on the goal line beside the goal are left to the clear strategy
+fn ball_on_side_wall(c: &Context) -> bool {
+    let (_, _, ok) = c.data.coordinates;
+    let (bx, by) = c.ball;
+    let params = &c.data.params;
+    let max_y = FIELD_LENGTH - FIELD_MARGIN_Y - params.striker_distance;
+
+    ok && !c.data.goalie
+        && !c.ball_lost
+        && c.dist < params.wall_range
+        && !(FIELD_MARGIN + params.wall_distance
+            ..=FIELD_WIDTH - FIELD_MARGIN - params.wall_distance)
+            .contains(&bx)
+        && by + BALLCAP_DISTANCE + params.wall_approach_distance < max_y
+}
+
+fn outside_play_area(c: &Context) -> bool {
+    let (_, y, ok) = c.data.coordinates;
+    let margin = c.data.params.play_area_margin;
+    ok && !((FIELD_MARGIN_Y - margin)..=FIELD_LENGTH - FIELD_MARGIN_Y + margin).contains(&y)
+}
+
+fn ball_lost(c: &Context) -> bool {
+    c.ball_lost
+}
+
+fn ball_rolling(c: &Context) -> bool {
+    let (_, _, ok) = c.data.coordinates;
+    let (_, _, bok) = c.data.ball;
+    let params = &c.data.params;
+
+    ok && !c.data.goalie
+        && bok
+        && c.ball_speed > params.intercept_speed
+        && c.dist > params.intercept_distance
+}
+
+fn ball_far(c: &Context) -> bool {
+    c.dist > c.data.params.far_distance
+}
+
+fn ball_behind(c: &Context) -> bool {
+    let (x, y, ok) = c.data.coordinates;
+    let (bx, by) = c.ball;
+    let striker_distance = if !c.data.goalie {
+        c.data.params.striker_distance
+    } else {
+        0.
+    };
+
+    ok && (by > y || (by + BALLCAP_DISTANCE > y && (x - bx).abs() > BALLCAP_WIDTH / 2.))
+        && by > FIELD_LENGTH - FIELD_MARGIN_Y - CLEARANCE_Y - striker_distance
+}
+
+// the goalie stays in goal for anything the striker would attack, unless it has just pushed out
+fn goalie_guarding(c: &Context) -> bool {
+    c.data.goalie && !c.goalie_attacking && (ball_far(c) || !ball_behind(c))
+}
+
+fn always(_: &Context) -> bool {
+    true
+}
+
+const RULES: [Rule<Context>; 17] = [
+    Rule::preempt(Strategy::None, game_stopped, "game stopped"),
+    Rule::preempt(Strategy::None, stuck, "stuck"),
+    Rule::preempt(Strategy::None, following_trajectory, "following trajectory"),
+    Rule::preempt(Strategy::Bounds, on_line, "line detected"),
+    Rule::preempt(
+        Strategy::Kickoff,
+        waiting_for_kickoff,
+        "waiting for kickoff",
+    ),
+    Rule::new(Strategy::Script, script_active, "script loaded"),
+    Rule::new(Strategy::Goalie, goalie_ball_in_corner, "ball in corner"),
+    Rule::new(Strategy::Goalie, goalie_clearing, "ball in penalty area"),
+    Rule::new(Strategy::Wall, ball_on_side_wall, "ball on side wall"),
+    Rule::new(Strategy::Clear, ball_in_corner, "ball in corner"),
+    Rule::new(Strategy::GetOut, outside_play_area, "outside play area"),
+    Rule::new(Strategy::NoBall, ball_lost, "ball lost"),
+    Rule::new(Strategy::Intercept, ball_rolling, "ball rolling"),
+    Rule::new(Strategy::Goalie, goalie_guarding, "guarding goal"),
+    Rule::new(Strategy::Attack, ball_far, "ball far"),
+    Rule::new(Strategy::Defence, ball_behind, "ball behind"),
+    Rule::new(Strategy::Attack, always, "ball ahead"),
+];
+
+#[derive(Default)]
+struct Behaviours {
+    attack: AttackState,
+    bounds: BoundsState,
+    clear: ClearState,
+    defence: DefenceState,
+    get_out: GetOutState,
+    goalie: GoalieState,
+    intercept: InterceptState,
+    kickoff: KickoffState,
+    no_ball: NoBallState,
+    script: ScriptState,
+    wall: WallState,
+}
+
+macro_rules! dispatch {
+    ($behaviours:expr, $strategy:expr, $method:ident($($arg:expr),*)) => {
+        match $strategy {
+            Strategy::Attack => $behaviours.attack.$method($($arg),*).await,
+            Strategy::Bounds => $behaviours.bounds.$method($($arg),*).await,
+            Strategy::Clear => $behaviours.clear.$method($($arg),*).await,
+            Strategy::Defence => $behaviours.defence.$method($($arg),*).await,
+            Strategy::GetOut => $behaviours.get_out.$method($($arg),*).await,
+            Strategy::Goalie => $behaviours.goalie.$method($($arg),*).await,
+            Strategy::Intercept => $behaviours.intercept.$method($($arg),*).await,
+            Strategy::Kickoff => $behaviours.kickoff.$method($($arg),*).await,
+            Strategy::NoBall => $behaviours.no_ball.$method($($arg),*).await,
+            Strategy::Script => $behaviours.script.$method($($arg),*).await,
+            Strategy::Wall => $behaviours.wall.$method($($arg),*).await,
+            Strategy::None => {}
+        }
+    };
 }
 
 #[embassy_executor::task]
@@ -66,19 +305,12 @@ async fn strategy_task() {
     let mut lines = (false, false, false, false);
     let mut is_camera = false;
 
-    let mut last_strategy = Strategy::None;
-    let mut last_changed = Instant::now();
+    let mut machine = Machine::default();
+    let mut behaviours = Behaviours::default();
+
     let mut last_ball_found = Instant::now();
     let mut last_goalie_attacked = Instant::from_millis(0);
 
-    let mut state_attack = AttackState::default();
-    let mut state_bounds = BoundsState::default();
-    let mut state_clear = ClearState::default();
-    let mut state_defence = DefenceState::default();
-    let mut state_get_out = GetOutState::default();
-    let mut state_goalie = GoalieState::default();
-    let mut state_no_ball = NoBallState::default();
-
     loop {
         match select3(
             LINE_SIGNAL.wait(),
@@ -93,7 +325,8 @@ async fn strategy_task() {
                 is_camera = false;
             }
             Either3::Second(data) => {
-                ball = read_mutex!(BALL_MUTEX);
+                // the world model covers for our camera with the teammate's sighting
+                ball = world::ball().await;
                 coordinates = read_mutex!(COORDINATE_MUTEX);
                 if let WaitResult::Message(data2) = data {
                     is_camera = data2;
@@ -105,17 +338,18 @@ async fn strategy_task() {
             }
         }
 
-        let goalie = get_config!(goalie);
+        let goalie = read_mutex!(ROLE_MUTEX) == Role::Goalie;
         let data = Data {
             ball,
+            ball_velocity: read_mutex!(BALL_VELOCITY_MUTEX),
             coordinates,
             captured,
             lines,
             goalie,
             is_camera,
+            params: read_mutex!(PARAMS),
         };
 
-        let mut strategy;
         let (x, y, ok) = data.coordinates;
         let (mut bx, mut by, bok) = data.ball;
 
@@ -131,121 +365,51 @@ async fn strategy_task() {
             last_ball_found = Instant::now();
         }
 
-        let (dist, _) = construct_vector(x - bx, y - by);
-
         let no_ball_duration = if !goalie {
-            NO_BALL_DURATION
+            data.params.no_ball_duration
         } else {
-            GOALIE_NO_BALL_DURATION
+            data.params.goalie_no_ball_duration
         };
 
-        let striker_distance = if !goalie { STRIKER_DISTANCE } else { 0. };
-
-        if ok
-            && dist < 50.
-            && last_ball_found.elapsed().as_millis() < no_ball_duration
-            && !(FIELD_MARGIN_X..=FIELD_WIDTH - FIELD_MARGIN_X).contains(&bx)
-            && by < FIELD_MARGIN + 10.
-            && by < y
-        {
-            strategy = Strategy::Clear;
-        } else if ok && !((FIELD_MARGIN_Y - 5.)..=FIELD_LENGTH - FIELD_MARGIN_Y + 5.).contains(&y) {
-            strategy = Strategy::GetOut;
-        } else if last_ball_found.elapsed().as_millis() > no_ball_duration {
-            strategy = Strategy::NoBall;
-        } else if dist > 50. {
-            strategy = Strategy::Attack;
-        } else if ok
-            && (by > y || (by + BALLCAP_DISTANCE > y && (x - bx).abs() > BALLCAP_WIDTH / 2.))
-            && by > FIELD_LENGTH - FIELD_MARGIN_Y - CLEARANCE_Y - striker_distance
-        {
-            strategy = Strategy::Defence;
-        } else {
-            strategy = Strategy::Attack;
+        if machine.current() == Strategy::Goalie && behaviours.goalie.pushing {
+            last_goalie_attacked = Instant::now();
         }
 
-        if goalie && (strategy == Strategy::Clear || strategy == Strategy::Attack) {
-            if last_strategy == Strategy::Goalie && state_goalie.pushing {
-                last_goalie_attacked = Instant::now();
-            }
-            if last_goalie_attacked.elapsed().as_millis() < GOALIE_ATTACK_DURATION
-                && strategy == Strategy::Attack
-            {
-                strategy = Strategy::Attack;
-            } else {
-                strategy = Strategy::Goalie;
-            }
-        }
+        let (dist, _) = construct_vector(x - bx, y - by);
+        let (ball_speed, _) = construct_vector(data.ball_velocity.0, data.ball_velocity.1);
 
-        if lines.0 || lines.1 || lines.2 || lines.3 {
-            strategy = Strategy::Bounds;
-            last_changed = Instant::now();
-        } else if last_strategy != Strategy::NoBall
-            && strategy != last_strategy
-            && ((last_strategy == Strategy::Bounds
-                && last_changed.elapsed().as_millis() < BOUNDS_DURATION)
-                || (last_strategy != Strategy::Bounds
-                    && last_changed.elapsed().as_millis() < STRATEGY_DURATION))
-        {
-            strategy = last_strategy;
-        } else {
-            last_changed = Instant::now();
-        }
+        let context = Context {
+            data,
+            game: read_mutex!(GAME_MUTEX),
+            ball: (bx, by),
+            dist,
+            ball_speed,
+            ball_lost: last_ball_found.elapsed().as_millis() > no_ball_duration,
+            goalie_attacking: last_goalie_attacked.elapsed().as_millis()
+                < data.params.goalie_attack_duration,
+            stuck: read_mutex!(STUCK_MUTEX),
+            trajectory: read_mutex!(TRAJECTORY_MUTEX),
+            script: script::active().await,
+        };
 
-        match strategy {
-            Strategy::Attack => {
-                if strategy != last_strategy {
-                    state_attack = AttackState::default();
-                }
-                attack::run(data, &mut state_attack).await;
-                debug_variable!("strategy", "attack");
-            }
-            Strategy::Bounds => {
-                if strategy != last_strategy {
-                    state_bounds = BoundsState::default();
-                }
-                bounds::run(data, &mut state_bounds).await;
-                debug_variable!("strategy", "bounds");
-            }
-            Strategy::Clear => {
-                if strategy != last_strategy {
-                    state_clear = ClearState::default();
-                }
-                clear::run(data, &mut state_clear).await;
-                debug_variable!("strategy", "clear");
-            }
-            Strategy::Defence => {
-                if strategy != last_strategy {
-                    state_defence = DefenceState::default();
-                }
-                defence::run(data, &mut state_defence).await;
-                debug_variable!("strategy", "defence");
-            }
-            Strategy::GetOut => {
-                if strategy != last_strategy {
-                    state_defence = DefenceState::default();
-                }
-                get_out::run(data, &mut state_get_out).await;
-                debug_variable!("strategy", "get_out");
-            }
-            Strategy::Goalie => {
-                if strategy != last_strategy {
-                    state_goalie = GoalieState::default();
-                }
-                goalie::run(data, &mut state_goalie).await;
-                debug_variable!("strategy", "goalie");
-            }
-            Strategy::NoBall => {
-                if strategy != last_strategy {
-                    state_no_ball = NoBallState::default();
-                }
-                no_ball::run(data, &mut state_no_ball).await;
-                debug_variable!("strategy", "no_ball");
-            }
-            _ => {}
+        if let Some(transition) = machine.update(&RULES, &context) {
+            info!(
+                "Strategy {} -> {} ({}) at {} ms",
+                transition.from.name(),
+                transition.to.name(),
+                transition.reason,
+                transition.time.as_millis()
+            );
+
+            dispatch!(behaviours, transition.from, exit());
+            dispatch!(behaviours, transition.to, enter(&data));
         }
 
-        last_strategy = strategy;
+        let strategy = machine.current();
+        dispatch!(behaviours, strategy, run(data));
+
+        debug_variable!("strategy", strategy.name());
+        debug_variable!("strategy reason", machine.reason());
     }
 }
 
@@ -1,21 +1,24 @@
 use crate::{
     modules::HEADING_SIGNAL,
-    strategy::{Data, COORDINATE_SIGNAL, FIELD_LENGTH, FIELD_MARGIN_Y, FIELD_WIDTH},
+    strategy::{
+        machine::Behaviour, Data, COORDINATE_SIGNAL, FIELD_LENGTH, FIELD_MARGIN_Y, FIELD_WIDTH,
+    },
 };
 use num_traits::Float;
 
-const NO_BALL_DISTANCE: f32 = 35.;
-const GOALIE_NO_BALL_DISTANCE: f32 = 5.;
-const CHECK_DISTANCE: f32 = 5.;
-const DISTANCE_THRESHOLD_Y: f32 = 10.;
-const DISTANCE_THRESHOLD_X: f32 = 10.;
-
 #[derive(Default)]
 pub struct NoBallState {
     pub check_left: bool,
 }
 
+impl Behaviour for NoBallState {
+    async fn run(&mut self, data: Data) {
+        run(data, self).await;
+    }
+}
+
 pub async fn run(data: Data, state: &mut NoBallState) {
+    let params = data.params;
     let (x, y, ok) = data.coordinates;
     let goalie = data.goalie;
 
@@ -27,22 +30,24 @@ pub async fn run(data: Data, state: &mut NoBallState) {
     }
 
     let no_ball_distance = if !goalie {
-        NO_BALL_DISTANCE
+        params.no_ball_distance
     } else {
-        GOALIE_NO_BALL_DISTANCE
+        params.no_ball_goalie_distance
     };
 
     let mut new_x = FIELD_WIDTH / 2.;
     let new_y = FIELD_LENGTH - FIELD_MARGIN_Y - no_ball_distance;
 
-    if (new_y - y).abs() > DISTANCE_THRESHOLD_Y || (new_x - x).abs() > DISTANCE_THRESHOLD_X {
+    if (new_y - y).abs() > params.no_ball_threshold_y
+        || (new_x - x).abs() > params.no_ball_threshold_x
+    {
         COORDINATE_SIGNAL.signal((new_x, new_y));
         return;
     }
 
-    if x < new_x - CHECK_DISTANCE {
+    if x < new_x - params.no_ball_check_distance {
         state.check_left = false;
-    } else if x > new_x + CHECK_DISTANCE {
+    } else if x > new_x + params.no_ball_check_distance {
         state.check_left = true;
     }
 
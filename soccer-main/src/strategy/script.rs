@@ -0,0 +1,137 @@
+use crate::{
+    modules::{
+        vm,
+        vm::{Fault, MAX_CODE, REGISTERS},
+        COORDINATE_SIGNAL, GOAL_MUTEX, HEADING_MUTEX, HEADING_SIGNAL,
+    },
+    strategy::{machine::Behaviour, Data},
+    utils::{debug::debug_variable, read_mutex},
+};
+use defmt::{info, warn};
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex};
+use embassy_time::Instant;
+use heapless::Vec;
+
+// scripts load ball x, y, seen, vx and vy, then x, y, position ok and heading, then goal x, y and
+// seen, then captured, the front, left, right and back lines, goalie and seconds since starting
+const INPUTS: usize = 19;
+
+struct Script {
+    code: Vec<u8, MAX_CODE>,
+    fault: Option<Fault>,
+}
+
+static SCRIPT: Mutex<CriticalSectionRawMutex, Script> = Mutex::new(Script {
+    code: Vec::new(),
+    fault: None,
+});
+
+pub async fn load(code: &[u8]) -> Result<(), Fault> {
+    vm::validate(code, INPUTS)?;
+
+    let mut script = SCRIPT.lock().await;
+    script.code = Vec::from_slice(code).map_err(|_| Fault::TooLong)?;
+    script.fault = None;
+
+    info!("Loaded script of {} bytes", code.len());
+    Ok(())
+}
+
+pub async fn unload() {
+    let mut script = SCRIPT.lock().await;
+    script.code.clear();
+    script.fault = None;
+
+    info!("Unloaded script");
+}
+
+// a loaded script plays instead of the built-in strategies until it faults
+pub async fn active() -> bool {
+    let script = SCRIPT.lock().await;
+    !script.code.is_empty() && script.fault.is_none()
+}
+
+pub async fn status() -> (usize, Option<Fault>) {
+    let script = SCRIPT.lock().await;
+    (script.code.len(), script.fault)
+}
+
+pub struct ScriptState {
+    registers: [f32; REGISTERS],
+    started: Instant,
+}
+
+impl Default for ScriptState {
+    fn default() -> Self {
+        Self {
+            registers: [0.; REGISTERS],
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Behaviour for ScriptState {
+    async fn run(&mut self, data: Data) {
+        run(data, self).await;
+    }
+}
+
+fn flag(value: bool) -> f32 {
+    if value {
+        1.
+    } else {
+        0.
+    }
+}
+
+pub async fn run(data: Data, state: &mut ScriptState) {
+    let (bx, by, bok) = data.ball;
+    let (vx, vy) = data.ball_velocity;
+    let (x, y, ok) = data.coordinates;
+    let (gx, gy, gok) = read_mutex!(GOAL_MUTEX);
+    let (front, left, right, back) = data.lines;
+
+    let inputs: [f32; INPUTS] = [
+        bx,
+        by,
+        flag(bok),
+        vx,
+        vy,
+        x,
+        y,
+        flag(ok),
+        read_mutex!(HEADING_MUTEX),
+        gx,
+        gy,
+        flag(gok),
+        flag(data.captured),
+        flag(front),
+        flag(left),
+        flag(right),
+        flag(back),
+        flag(data.goalie),
+        state.started.elapsed().as_millis() as f32 / 1000.,
+    ];
+
+    let mut script = SCRIPT.lock().await;
+    if script.fault.is_some() {
+        return;
+    }
+
+    match vm::run(&script.code, &inputs, &mut state.registers) {
+        Ok(output) => {
+            if let Some(target) = output.target {
+                COORDINATE_SIGNAL.signal(target);
+            }
+            if let Some(heading) = output.heading {
+                HEADING_SIGNAL.signal(heading);
+            }
+        }
+        Err(fault) => {
+            // the strategy selector falls back to the built-in strategies from the next update
+            warn!("Script faulted: {}", fault.name());
+            script.fault = Some(fault);
+            debug_variable!("script fault", fault.name());
+        }
+    }
+}
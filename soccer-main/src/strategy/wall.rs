@@ -0,0 +1,93 @@
+use crate::{
+    constants::BALLCAP_DISTANCE,
+    modules::{orbit::facing, COORDINATE_SIGNAL, HEADING_SIGNAL},
+    strategy::{machine::Behaviour, Data, FIELD_LENGTH, FIELD_WIDTH},
+    utils::{clamp_angle, construct_vector, debug::debug_variable},
+};
+use embassy_time::Instant;
+
+// the ball cannot be got behind against a side wall, so the robot comes up along the wall from our
+// side, takes the ball and turns it out towards the middle of the field before backing off
+#[derive(Default)]
+enum Stage {
+    #[default]
+    Approach,
+    Push,
+    Flick(Instant),
+    Retreat(Instant),
+}
+
+#[derive(Default)]
+pub struct WallState {
+    stage: Stage,
+}
+
+impl Behaviour for WallState {
+    async fn run(&mut self, data: Data) {
+        run(data, self).await;
+    }
+}
+
+pub async fn run(data: Data, state: &mut WallState) {
+    let params = data.params;
+    let (x, y, _) = data.coordinates;
+    let (bx, by, _) = data.ball;
+
+    let behind = (bx, by + BALLCAP_DISTANCE + params.wall_approach_distance);
+    let ball_heading = facing((bx, by), (x, y));
+
+    match state.stage {
+        Stage::Approach => {
+            let (distance, _) = construct_vector(behind.0 - x, behind.1 - y);
+            if distance < params.wall_approach_tolerance {
+                state.stage = Stage::Push;
+            }
+
+            HEADING_SIGNAL.signal(0.);
+            COORDINATE_SIGNAL.signal(behind);
+        }
+        Stage::Push => {
+            let (distance, _) = construct_vector(bx - x, by - y);
+            if data.captured || distance < BALLCAP_DISTANCE + params.wall_push_distance {
+                state.stage = Stage::Flick(Instant::now());
+            } else if y < by {
+                // the ball got past us, so line up behind it again
+                state.stage = Stage::Approach;
+            }
+
+            HEADING_SIGNAL.signal(ball_heading);
+            COORDINATE_SIGNAL.signal((bx, by + BALLCAP_DISTANCE));
+        }
+        Stage::Flick(start) => {
+            if start.elapsed().as_millis() > params.wall_flick_duration {
+                state.stage = Stage::Retreat(Instant::now());
+            }
+
+            // turn towards the centre as far as the flick allows while dragging the ball inwards
+            let center = (FIELD_WIDTH / 2., FIELD_LENGTH / 2.);
+            let turn = clamp_angle(facing(center, (x, y)) - ball_heading);
+            HEADING_SIGNAL.signal(clamp_angle(
+                ball_heading + turn.clamp(-params.wall_flick_angle, params.wall_flick_angle),
+            ));
+            COORDINATE_SIGNAL.signal((FIELD_WIDTH / 2., by));
+        }
+        Stage::Retreat(start) => {
+            if start.elapsed().as_millis() > params.wall_retreat_duration {
+                state.stage = Stage::Approach;
+            }
+
+            HEADING_SIGNAL.signal(0.);
+            COORDINATE_SIGNAL.signal((x, by + BALLCAP_DISTANCE + params.wall_approach_distance));
+        }
+    }
+
+    debug_variable!(
+        "wall stage",
+        match state.stage {
+            Stage::Approach => "approach",
+            Stage::Push => "push",
+            Stage::Flick(_) => "flick",
+            Stage::Retreat(_) => "retreat",
+        }
+    );
+}
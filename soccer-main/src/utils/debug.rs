@@ -3,9 +3,10 @@ use heapless::{FnvIndexMap, String, Vec};
 use serde::Deserialize;
 
 type Variable = String<16>;
-type VariableMap = FnvIndexMap<&'static str, Variable, 32>;
+// a power of two with room to spare over the number of distinct debug_variable! names
+type VariableMap = FnvIndexMap<&'static str, Variable, 128>;
 type Function = Vec<&'static str, 4>;
-type FunctionMap = FnvIndexMap<&'static str, Function, 16>;
+type FunctionMap = FnvIndexMap<&'static str, Function, 32>;
 
 pub type FunctionArgs = FnvIndexMap<String<32>, String<32>, 4>;
 
@@ -1,24 +1,124 @@
 use crate::{
     bootloader::{Command, BOOTLOADER_CHANNEL},
     config::set_config,
-    modules::{movement, HEADING_SIGNAL},
-    utils,
-    utils::debug::debug_functions,
+    modules::{
+        calibration, game, game::Event, movement, side, trajectory, trajectory::Waypoint, tuning,
+        Frame, Velocity, HEADING_SIGNAL, VELOCITY_SIGNAL,
+    },
+    strategy::kickoff,
+    utils::debug::{debug_functions, debug_variable},
 };
+use defmt::warn;
 
 debug_functions! {
     async fn start() {
-        utils::start().await;
+        game::send(Event::Start).await;
+    }
+
+    async fn pause() {
+        game::send(Event::Pause).await;
+    }
+
+    async fn resume() {
+        game::send(Event::Resume).await;
+    }
+
+    async fn kickoff(attacking: bool) {
+        game::send(Event::Kickoff(attacking)).await;
     }
 
     async fn drive(speed: f32, angle: f32, rotation: f32) {
-        movement::drive(speed, angle, rotation);
+        movement::drive(speed, angle, rotation).await;
+    }
+
+    async fn calibrate_motors(apply: bool) {
+        calibration::calibrate_motors(apply).await;
+    }
+
+    async fn velocity(x: f32, y: f32, field: bool) {
+        let frame = if field { Frame::Field } else { Frame::Robot };
+        VELOCITY_SIGNAL.signal(Velocity {
+            x,
+            y,
+            omega: None,
+            frame,
+        });
+    }
+
+    async fn add_waypoint(x: f32, y: f32, heading: f32, speed: f32) {
+        let added = trajectory::add_waypoint(Waypoint {
+            x,
+            y,
+            heading,
+            speed,
+        })
+        .await;
+
+        if !added {
+            warn!("Trajectory is full, dropped waypoint");
+        }
+        debug_variable!("waypoint dropped", !added);
+    }
+
+    async fn follow_trajectory() {
+        trajectory::start().await;
+    }
+
+    async fn clear_trajectory() {
+        trajectory::clear().await;
+    }
+
+    async fn add_opening_waypoint(x: f32, y: f32, heading: f32, speed: f32) {
+        let added = kickoff::add_opening_waypoint(Waypoint {
+            x,
+            y,
+            heading,
+            speed,
+        })
+        .await;
+
+        if !added {
+            warn!("Opening is full, dropped waypoint");
+        }
+        debug_variable!("opening waypoint dropped", !added);
+    }
+
+    async fn clear_opening() {
+        kickoff::clear_opening().await;
+    }
+
+    async fn set_kickoff_position(goalie: bool, x: f32, y: f32) {
+        if goalie {
+            set_config!(kickoff_goalie, (x, y));
+        } else {
+            set_config!(kickoff_striker, (x, y));
+        }
     }
 
     async fn set_goalie(enable: bool) {
         set_config!(goalie, enable);
     }
 
+    async fn set_mirrored(enable: bool) {
+        side::set(enable).await;
+    }
+
+    async fn set_planning(enable: bool) {
+        set_config!(planning, enable);
+    }
+
+    async fn set_stuck_duration(duration: u64) {
+        set_config!(stuck_duration, duration);
+    }
+
+    async fn set_rotation_priority(priority: f32) {
+        set_config!(rotation_priority, priority.clamp(0., 1.));
+    }
+
+    async fn set_braking_distance(distance: f32) {
+        set_config!(braking_distance, distance.max(0.));
+    }
+
     async fn set_pid(p1: f32, d1: f32, p2: f32, d2: f32) {
         set_config!(pid_p, p1);
         set_config!(pid_d, d1);
@@ -27,12 +127,28 @@ debug_functions! {
         HEADING_SIGNAL.signal(0.01);
     }
 
+    async fn set_heading_pid(p: f32, i: f32, d: f32, i_max: f32) {
+        set_config!(pid_p, p);
+        set_config!(pid_i, i);
+        set_config!(pid_d, d);
+        set_config!(pid_i_max, i_max);
+        HEADING_SIGNAL.signal(0.01);
+    }
+
+    async fn tune_heading(apply: bool) {
+        tuning::tune_heading(apply).await;
+    }
+
+    async fn tune_position(apply: bool) {
+        tuning::tune_position(apply).await;
+    }
+
     async fn print_imu(enable: bool) {
         set_config!(print_imu, enable);
     }
 
     async fn stop() {
-        utils::stop().await;
+        game::send(Event::Stop).await;
     }
 
     async fn restart() {
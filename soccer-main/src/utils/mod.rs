@@ -1,8 +1,8 @@
 use crate::{
     config::{get_config, set_config},
     modules::{
-        movement::{drive, ROTATION_SIGNAL, SPEED_ANGLE_SIGNAL},
-        COORDINATE_MUTEX, COORDINATE_SIGNAL, HEADING_MUTEX, HEADING_SIGNAL,
+        movement::{brake, ROTATION_SIGNAL, SPEED_ANGLE_SIGNAL},
+        side, trajectory, COORDINATE_MUTEX, COORDINATE_SIGNAL, HEADING_MUTEX, HEADING_SIGNAL,
     },
 };
 use embassy_time::Timer;
@@ -66,12 +66,22 @@ pub async fn start() {
             }
         }
 
+        let mirrored = side::mirrored().await;
+        let unmirrored = |heading| {
+            if mirrored {
+                side::mirror_heading(heading)
+            } else {
+                heading
+            }
+        };
+
         let mut total = 0.;
         for _ in 0..40 {
-            total += read_mutex!(HEADING_MUTEX);
+            total += unmirrored(read_mutex!(HEADING_MUTEX));
             Timer::after_millis(5).await;
         }
-        let zero_angle = total / 40.;
+        // the robot is started facing the goal it attacks, whichever side that is
+        let zero_angle = unmirrored(total / 40.);
         set_config!(angle, zero_angle);
     }
     set_config!(started, true);
@@ -79,6 +89,7 @@ pub async fn start() {
 
 pub async fn stop() {
     set_config!(started, false);
+    trajectory::cancel().await;
     let (x, y, _) = read_mutex!(COORDINATE_MUTEX);
     for _ in 0..3 {
         HEADING_SIGNAL.signal(0.);
@@ -86,5 +97,5 @@ pub async fn stop() {
         SPEED_ANGLE_SIGNAL.signal((0., 0.));
         ROTATION_SIGNAL.signal(0.);
     }
-    drive(0., 0., 0.);
+    brake().await;
 }
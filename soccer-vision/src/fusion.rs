@@ -184,7 +184,7 @@ async fn fusion_task() {
         }
 
         let angle = clamp_angle(fusion.quaternion.euler().angle.yaw);
-        let _ = UART_CHANNEL.try_send(Command::Positioning { angle });
+        let _ = UART_CHANNEL.try_send(Command::Positioning { angle, rate: gyr.z });
     }
 }
 
@@ -34,6 +34,7 @@ pub enum Command {
     },
     Positioning {
         angle: f32,
+        rate: f32,
     },
 }
 
@@ -65,10 +66,11 @@ async fn uart_tx_task(mut tx: UartTx<'static, UART0, Async>) {
                     .await;
                 let _ = tx.send_break(0).await;
             }
-            Command::Positioning { angle } => {
+            Command::Positioning { angle, rate } => {
                 let angle = ((angle * 128.).round() as i16).to_le_bytes();
+                let rate = ((rate * 16.).round() as i16).to_le_bytes();
 
-                let _ = tx.write(&[2, angle[0], angle[1]]).await;
+                let _ = tx.write(&[2, angle[0], angle[1], rate[0], rate[1]]).await;
                 let _ = tx.send_break(0).await;
             }
         }